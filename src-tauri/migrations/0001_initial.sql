@@ -1,6 +1,5 @@
-PRAGMA foreign_keys = ON;
--- DROP TABLE IF EXISTS groups;
--- DROP TABLE IF EXISTS hosts; 
+-- Baseline schema. IF NOT EXISTS because databases from before the
+-- migration runner already have these tables at user_version 0.
 /* =========================
    TABLE: groups
    ========================= */
@@ -0,0 +1,32 @@
+/* =========================
+   TABLE: settings
+   ========================= */
+CREATE TABLE IF NOT EXISTS settings (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+/* =========================
+   TABLE: tunnel_profiles
+   ========================= */
+CREATE TABLE IF NOT EXISTS tunnel_profiles (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    kind TEXT NOT NULL, -- local | remote | dynamic
+    host_id INTEGER NOT NULL,
+    bind_host TEXT NOT NULL DEFAULT '127.0.0.1',
+    bind_port INTEGER NOT NULL,
+    target_host TEXT NOT NULL DEFAULT '',
+    target_port INTEGER NOT NULL DEFAULT 0,
+    auto_start TEXT NOT NULL DEFAULT 'manual', -- manual | app_launch | host_session
+    restart_on_failure INTEGER NOT NULL DEFAULT 0,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+
+    FOREIGN KEY (host_id)
+        REFERENCES hosts(id)
+        ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_tunnel_profiles_host
+    ON tunnel_profiles(host_id);
@@ -0,0 +1,33 @@
+/* =========================
+   TABLE: import_sources
+   Inventories that can be re-synced later
+   ========================= */
+CREATE TABLE IF NOT EXISTS import_sources (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    kind TEXT NOT NULL, -- ansible
+    path TEXT NOT NULL,
+    group_id INTEGER,
+    last_synced_at DATETIME,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+
+    FOREIGN KEY (group_id)
+        REFERENCES groups(id)
+        ON DELETE CASCADE
+);
+
+/* =========================
+   TABLE: import_source_hosts
+   Hosts created or updated by the last sync of a source
+   ========================= */
+CREATE TABLE IF NOT EXISTS import_source_hosts (
+    source_id INTEGER NOT NULL,
+    host_id INTEGER NOT NULL,
+
+    PRIMARY KEY (source_id, host_id),
+    FOREIGN KEY (source_id)
+        REFERENCES import_sources(id)
+        ON DELETE CASCADE,
+    FOREIGN KEY (host_id)
+        REFERENCES hosts(id)
+        ON DELETE CASCADE
+);
@@ -0,0 +1,28 @@
+/* =========================
+   TABLE: tags
+   Names like `prod` or `env:prod`; the part before ':' is only a convention
+   ========================= */
+CREATE TABLE IF NOT EXISTS tags (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+/* =========================
+   TABLE: host_tags
+   ========================= */
+CREATE TABLE IF NOT EXISTS host_tags (
+    host_id INTEGER NOT NULL,
+    tag_id INTEGER NOT NULL,
+
+    PRIMARY KEY (host_id, tag_id),
+    FOREIGN KEY (host_id)
+        REFERENCES hosts(id)
+        ON DELETE CASCADE,
+    FOREIGN KEY (tag_id)
+        REFERENCES tags(id)
+        ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_host_tags_tag
+    ON host_tags(tag_id);
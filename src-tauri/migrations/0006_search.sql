@@ -0,0 +1,49 @@
+ALTER TABLE hosts ADD COLUMN notes TEXT;
+
+/* =========================
+   TABLE: search_index (FTS5)
+   Rebuilt from hosts/groups/tags when `search_index_state.dirty` is set
+   ========================= */
+CREATE VIRTUAL TABLE IF NOT EXISTS search_index USING fts5(
+    name,
+    address,
+    username,
+    tags,
+    notes,
+    group_path,
+    kind UNINDEXED, -- host | group
+    ref_id UNINDEXED,
+    tokenize = 'unicode61 remove_diacritics 2',
+    prefix = '2 3'
+);
+
+CREATE TABLE IF NOT EXISTS search_index_state (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    dirty INTEGER NOT NULL DEFAULT 1
+);
+
+INSERT OR IGNORE INTO search_index_state (id, dirty) VALUES (1, 1);
+
+/* =========================
+   TRIGGERS: mark the index stale
+   ========================= */
+CREATE TRIGGER IF NOT EXISTS trg_search_hosts_ins AFTER INSERT ON hosts
+BEGIN UPDATE search_index_state SET dirty = 1; END;
+CREATE TRIGGER IF NOT EXISTS trg_search_hosts_upd AFTER UPDATE ON hosts
+BEGIN UPDATE search_index_state SET dirty = 1; END;
+CREATE TRIGGER IF NOT EXISTS trg_search_hosts_del AFTER DELETE ON hosts
+BEGIN UPDATE search_index_state SET dirty = 1; END;
+
+CREATE TRIGGER IF NOT EXISTS trg_search_groups_ins AFTER INSERT ON groups
+BEGIN UPDATE search_index_state SET dirty = 1; END;
+CREATE TRIGGER IF NOT EXISTS trg_search_groups_upd AFTER UPDATE ON groups
+BEGIN UPDATE search_index_state SET dirty = 1; END;
+CREATE TRIGGER IF NOT EXISTS trg_search_groups_del AFTER DELETE ON groups
+BEGIN UPDATE search_index_state SET dirty = 1; END;
+
+CREATE TRIGGER IF NOT EXISTS trg_search_tags_upd AFTER UPDATE ON tags
+BEGIN UPDATE search_index_state SET dirty = 1; END;
+CREATE TRIGGER IF NOT EXISTS trg_search_host_tags_ins AFTER INSERT ON host_tags
+BEGIN UPDATE search_index_state SET dirty = 1; END;
+CREATE TRIGGER IF NOT EXISTS trg_search_host_tags_del AFTER DELETE ON host_tags
+BEGIN UPDATE search_index_state SET dirty = 1; END;
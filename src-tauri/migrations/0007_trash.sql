@@ -0,0 +1,28 @@
+/* =========================
+   Soft delete: trashed rows keep their place in the tree and carry the
+   trash entry they were deleted with, so a subtree restores as a unit
+   ========================= */
+ALTER TABLE groups ADD COLUMN deleted_at DATETIME;
+ALTER TABLE groups ADD COLUMN trash_id INTEGER;
+ALTER TABLE hosts ADD COLUMN deleted_at DATETIME;
+ALTER TABLE hosts ADD COLUMN trash_id INTEGER;
+
+/* =========================
+   TABLE: trash
+   One entry per delete action
+   ========================= */
+CREATE TABLE IF NOT EXISTS trash (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    kind TEXT NOT NULL, -- group | host
+    ref_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    group_count INTEGER NOT NULL DEFAULT 0,
+    host_count INTEGER NOT NULL DEFAULT 0,
+    deleted_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_groups_trash
+    ON groups(trash_id);
+
+CREATE INDEX IF NOT EXISTS idx_hosts_trash
+    ON hosts(trash_id);
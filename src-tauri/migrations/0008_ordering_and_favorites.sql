@@ -0,0 +1,67 @@
+/* =========================
+   Manual order among siblings and favorites
+   ========================= */
+ALTER TABLE groups ADD COLUMN sort_order INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE groups ADD COLUMN favorite INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE hosts ADD COLUMN sort_order INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE hosts ADD COLUMN favorite INTEGER NOT NULL DEFAULT 0;
+
+-- existing rows start out alphabetical
+UPDATE groups SET sort_order = (
+    SELECT r.n FROM (
+        SELECT id, ROW_NUMBER() OVER (PARTITION BY parent_id ORDER BY name COLLATE NOCASE, id) AS n
+        FROM groups
+    ) r WHERE r.id = groups.id
+);
+
+UPDATE hosts SET sort_order = (
+    SELECT r.n FROM (
+        SELECT id, ROW_NUMBER() OVER (PARTITION BY group_id ORDER BY name COLLATE NOCASE, id) AS n
+        FROM hosts
+    ) r WHERE r.id = hosts.id
+);
+
+CREATE INDEX IF NOT EXISTS idx_groups_favorite
+    ON groups(favorite);
+
+CREATE INDEX IF NOT EXISTS idx_hosts_favorite
+    ON hosts(favorite);
+
+/* =========================
+   TRIGGERS: new rows and rows moved to another parent go last
+   ========================= */
+CREATE TRIGGER IF NOT EXISTS trg_groups_order_ins AFTER INSERT ON groups
+WHEN NEW.sort_order = 0
+BEGIN
+    UPDATE groups SET sort_order = (
+        SELECT COALESCE(MAX(sort_order), 0) + 1 FROM groups
+        WHERE parent_id IS NEW.parent_id AND id != NEW.id
+    ) WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_groups_order_move AFTER UPDATE OF parent_id ON groups
+WHEN OLD.parent_id IS NOT NEW.parent_id
+BEGIN
+    UPDATE groups SET sort_order = (
+        SELECT COALESCE(MAX(sort_order), 0) + 1 FROM groups
+        WHERE parent_id IS NEW.parent_id AND id != NEW.id
+    ) WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_hosts_order_ins AFTER INSERT ON hosts
+WHEN NEW.sort_order = 0
+BEGIN
+    UPDATE hosts SET sort_order = (
+        SELECT COALESCE(MAX(sort_order), 0) + 1 FROM hosts
+        WHERE group_id IS NEW.group_id AND id != NEW.id
+    ) WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_hosts_order_move AFTER UPDATE OF group_id ON hosts
+WHEN OLD.group_id IS NOT NEW.group_id
+BEGIN
+    UPDATE hosts SET sort_order = (
+        SELECT COALESCE(MAX(sort_order), 0) + 1 FROM hosts
+        WHERE group_id IS NEW.group_id AND id != NEW.id
+    ) WHERE id = NEW.id;
+END;
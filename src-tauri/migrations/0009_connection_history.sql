@@ -0,0 +1,25 @@
+/* =========================
+   TABLE: connection_history
+   One row per terminal session, written by the SSH worker
+   ========================= */
+CREATE TABLE IF NOT EXISTS connection_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    host_id INTEGER NOT NULL,
+    started_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    ended_at DATETIME,
+    duration_ms INTEGER,
+    status TEXT NOT NULL DEFAULT 'running', -- running | closed | cancelled | failed | interrupted
+    exit_code INTEGER,
+    failure_kind TEXT, -- resolve | connect | handshake | auth | channel | output_limit | io
+    error TEXT,
+
+    FOREIGN KEY (host_id)
+        REFERENCES hosts(id)
+        ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_connection_history_host
+    ON connection_history(host_id, started_at);
+
+CREATE INDEX IF NOT EXISTS idx_connection_history_started
+    ON connection_history(started_at);
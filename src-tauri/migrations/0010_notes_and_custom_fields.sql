@@ -0,0 +1,68 @@
+ALTER TABLE groups ADD COLUMN notes TEXT;
+
+/* =========================
+   TABLES: host_fields, group_fields
+   Free-form key/value pairs, e.g. datacenter or owner
+   ========================= */
+CREATE TABLE IF NOT EXISTS host_fields (
+    host_id INTEGER NOT NULL,
+    key TEXT NOT NULL,
+    value TEXT NOT NULL DEFAULT '',
+    position INTEGER NOT NULL DEFAULT 0,
+
+    PRIMARY KEY (host_id, key),
+    FOREIGN KEY (host_id)
+        REFERENCES hosts(id)
+        ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS group_fields (
+    group_id INTEGER NOT NULL,
+    key TEXT NOT NULL,
+    value TEXT NOT NULL DEFAULT '',
+    position INTEGER NOT NULL DEFAULT 0,
+
+    PRIMARY KEY (group_id, key),
+    FOREIGN KEY (group_id)
+        REFERENCES groups(id)
+        ON DELETE CASCADE
+);
+
+/* =========================
+   TABLE: search_index (FTS5)
+   Recreated with a `fields` column; rebuilt on the next search
+   ========================= */
+DROP TABLE IF EXISTS search_index;
+
+CREATE VIRTUAL TABLE search_index USING fts5(
+    name,
+    address,
+    username,
+    tags,
+    notes,
+    fields,
+    group_path,
+    kind UNINDEXED, -- host | group
+    ref_id UNINDEXED,
+    tokenize = 'unicode61 remove_diacritics 2',
+    prefix = '2 3'
+);
+
+UPDATE search_index_state SET dirty = 1;
+
+/* =========================
+   TRIGGERS: mark the index stale
+   ========================= */
+CREATE TRIGGER IF NOT EXISTS trg_search_host_fields_ins AFTER INSERT ON host_fields
+BEGIN UPDATE search_index_state SET dirty = 1; END;
+CREATE TRIGGER IF NOT EXISTS trg_search_host_fields_upd AFTER UPDATE ON host_fields
+BEGIN UPDATE search_index_state SET dirty = 1; END;
+CREATE TRIGGER IF NOT EXISTS trg_search_host_fields_del AFTER DELETE ON host_fields
+BEGIN UPDATE search_index_state SET dirty = 1; END;
+
+CREATE TRIGGER IF NOT EXISTS trg_search_group_fields_ins AFTER INSERT ON group_fields
+BEGIN UPDATE search_index_state SET dirty = 1; END;
+CREATE TRIGGER IF NOT EXISTS trg_search_group_fields_upd AFTER UPDATE ON group_fields
+BEGIN UPDATE search_index_state SET dirty = 1; END;
+CREATE TRIGGER IF NOT EXISTS trg_search_group_fields_del AFTER DELETE ON group_fields
+BEGIN UPDATE search_index_state SET dirty = 1; END;
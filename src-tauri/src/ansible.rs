@@ -0,0 +1,878 @@
+use rusqlite::Connection;
+use serde::Serialize;
+use serde_yaml::Value;
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::Path,
+};
+use tauri::{AppHandle, Manager};
+
+use crate::db::Db;
+use crate::import::{
+    apply_import, import_hosts, preview_import, DuplicateMode, ImportHost, ImportPreview, ImportResult,
+};
+use crate::tags::{resolve_host_selector, HostSelector};
+use crate::trash::trash_host;
+
+/* =========================
+   MODELS
+========================= */
+
+/// Groups every inventory has implicitly; they do not become NetHopper groups
+const IMPLICIT_GROUPS: [&str; 2] = ["all", "ungrouped"];
+/// Upper bound for the hosts one pattern expands to, nested ranges included
+const MAX_PATTERN_HOSTS: usize = 10_000;
+
+#[derive(Default)]
+struct AnsibleGroup {
+    hosts: Vec<String>,
+    children: Vec<String>,
+    vars: HashMap<String, String>,
+}
+
+#[derive(Default)]
+struct AnsibleInventory {
+    /// In file order, so the first parent of a group wins
+    order: Vec<String>,
+    groups: HashMap<String, AnsibleGroup>,
+    host_vars: HashMap<String, HashMap<String, String>>,
+}
+
+impl AnsibleInventory {
+    fn group(&mut self, name: &str) -> &mut AnsibleGroup {
+        if !self.groups.contains_key(name) {
+            self.order.push(name.to_string());
+        }
+        self.groups.entry(name.to_string()).or_default()
+    }
+
+    fn add_host(&mut self, group: &str, host: &str, vars: HashMap<String, String>) {
+        let g = self.group(group);
+        if !g.hosts.iter().any(|h| h == host) {
+            g.hosts.push(host.to_string());
+        }
+        self.host_vars.entry(host.to_string()).or_default().extend(vars);
+    }
+}
+
+#[derive(Serialize)]
+pub struct ImportSource {
+    pub id: i64,
+    pub kind: String,
+    pub path: String,
+    pub group_id: Option<i64>,
+    pub last_synced_at: Option<String>,
+}
+
+/* =========================
+   TAURI COMMANDS
+========================= */
+
+#[tauri::command]
+pub fn preview_ansible_import(
+    path: String,
+    group_id: Option<i64>,
+    app: AppHandle,
+) -> Result<ImportPreview, String> {
+    let (hosts, warnings) = load_inventory(Path::new(&path))?;
+
+    let db = app.state::<Db>();
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    preview_import(&conn, hosts, group_id, warnings)
+}
+
+/// With `remember` the inventory is saved as an import source that
+/// `resync_import_source` can pick up again later
+#[tauri::command]
+pub fn import_ansible_inventory(
+    path: String,
+    group_id: Option<i64>,
+    duplicates: Option<DuplicateMode>,
+    remember: bool,
+    app: AppHandle,
+) -> Result<ImportResult, String> {
+    let (hosts, warnings) = load_inventory(Path::new(&path))?;
+
+    let db = app.state::<Db>();
+    let mut conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    let result = apply_import(&mut conn, hosts, group_id, duplicates.unwrap_or_default(), warnings)?;
+
+    if remember {
+        conn.execute(
+            "INSERT INTO import_sources (kind, path, group_id, last_synced_at)
+             VALUES ('ansible', ?, ?, CURRENT_TIMESTAMP)",
+            rusqlite::params![path, group_id],
+        )
+        .map_err(|e| e.to_string())?;
+        let source_id = conn.last_insert_rowid();
+        record_source_hosts(&conn, source_id, &result.host_ids)?;
+    }
+
+    Ok(result)
+}
+
+#[tauri::command]
+pub fn list_import_sources(db: tauri::State<Db>) -> Result<Vec<ImportSource>, String> {
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    let mut stmt = conn
+        .prepare("SELECT id, kind, path, group_id, last_synced_at FROM import_sources ORDER BY id")
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map([], |r| {
+            Ok(ImportSource {
+                id: r.get(0)?,
+                kind: r.get(1)?,
+                path: r.get(2)?,
+                group_id: r.get(3)?,
+                last_synced_at: r.get(4)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.filter_map(Result::ok).collect())
+}
+
+/// Re-reads the inventory and updates matching hosts. Only hosts this source
+/// imported before or that sit below its group are matched by address, so a
+/// sync never takes over hosts elsewhere in the tree. With `remove_missing`,
+/// hosts this source imported before that are gone from the inventory are deleted.
+#[tauri::command]
+pub fn resync_import_source(
+    id: i64,
+    remove_missing: bool,
+    app: AppHandle,
+) -> Result<ImportResult, String> {
+    let db = app.state::<Db>();
+
+    let (path, group_id): (String, Option<i64>) = {
+        let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+        conn.query_row(
+            "SELECT path, group_id FROM import_sources WHERE id = ?",
+            [id],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .map_err(|_| "Import source not found")?
+    };
+
+    let (hosts, warnings) = load_inventory(Path::new(&path))?;
+
+    let mut conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+    let previous = source_host_ids(&tx, id)?;
+    let scope: HashSet<i64> = resolve_host_selector(
+        &tx,
+        &HostSelector {
+            host_ids: previous.clone(),
+            group_ids: group_id.into_iter().collect(),
+            tag_expr: None,
+        },
+    )?
+    .into_iter()
+    .collect();
+
+    let mut result = ImportResult {
+        warnings,
+        ..Default::default()
+    };
+    import_hosts(&tx, hosts, group_id, DuplicateMode::Update, Some(&scope), &mut result)?;
+
+    if remove_missing {
+        let current: HashSet<i64> = result.host_ids.iter().copied().collect();
+        // removed hosts go to the trash, unless they were trashed by hand already
+        for host_id in previous.into_iter().filter(|h| !current.contains(h)) {
+            let live: bool = tx
+                .query_row(
+                    "SELECT EXISTS (SELECT 1 FROM hosts WHERE id = ? AND deleted_at IS NULL)",
+                    [host_id],
+                    |r| r.get(0),
+                )
+                .map_err(|e| e.to_string())?;
+            if live {
+                trash_host(&tx, host_id)?;
+            }
+        }
+    } else {
+        // hosts kept from earlier syncs stay tracked for a later removal
+        result.host_ids.extend(previous);
+    }
+
+    record_source_hosts(&tx, id, &result.host_ids)?;
+    tx.execute(
+        "UPDATE import_sources SET last_synced_at = CURRENT_TIMESTAMP WHERE id = ?",
+        [id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(result)
+}
+
+/// Forgets the source; its hosts stay in the inventory
+#[tauri::command]
+pub fn delete_import_source(id: i64, db: tauri::State<Db>) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    conn.execute("DELETE FROM import_sources WHERE id = ?", [id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn source_host_ids(conn: &Connection, source_id: i64) -> Result<Vec<i64>, String> {
+    let mut stmt = conn
+        .prepare("SELECT host_id FROM import_source_hosts WHERE source_id = ?")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([source_id], |r| r.get(0))
+        .map_err(|e| e.to_string())?;
+    Ok(rows.filter_map(Result::ok).collect())
+}
+
+fn record_source_hosts(conn: &Connection, source_id: i64, host_ids: &[i64]) -> Result<(), String> {
+    conn.execute("DELETE FROM import_source_hosts WHERE source_id = ?", [source_id])
+        .map_err(|e| e.to_string())?;
+
+    // deleted hosts would fail the foreign key
+    let mut stmt = conn
+        .prepare(
+            "INSERT OR IGNORE INTO import_source_hosts (source_id, host_id)
+             SELECT ?1, id FROM hosts WHERE id = ?2",
+        )
+        .map_err(|e| e.to_string())?;
+    for host_id in host_ids {
+        stmt.execute([source_id, *host_id]).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/* =========================
+   LOAD
+========================= */
+
+fn load_inventory(path: &Path) -> Result<(Vec<ImportHost>, Vec<String>), String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    let mut warnings = Vec::new();
+
+    let is_yaml = path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("yml") || e.eq_ignore_ascii_case("yaml"));
+    let mut inv = if is_yaml {
+        parse_yaml(&text, &mut warnings)?
+    } else {
+        parse_ini(&text, &mut warnings)
+    };
+
+    if let Some(dir) = path.parent() {
+        load_vars_dirs(dir, &mut inv, &mut warnings);
+    }
+
+    Ok((build_hosts(&inv, &mut warnings), warnings))
+}
+
+/// `group_vars/<group>.yml` and `host_vars/<host>.yml` (or a directory of
+/// YAML files per name) next to the inventory
+fn load_vars_dirs(dir: &Path, inv: &mut AnsibleInventory, warnings: &mut Vec<String>) {
+    let mut groups = inv.order.clone();
+    for implicit in IMPLICIT_GROUPS {
+        if !groups.iter().any(|g| g == implicit) {
+            groups.push(implicit.to_string());
+        }
+    }
+
+    for group in groups {
+        let vars = read_vars(&dir.join("group_vars"), &group, warnings);
+        if !vars.is_empty() {
+            let g = inv.group(&group);
+            // inline vars win over vars files
+            for (k, v) in vars {
+                g.vars.entry(k).or_insert(v);
+            }
+        }
+    }
+
+    let hosts: Vec<String> = inv.host_vars.keys().cloned().collect();
+    for host in hosts {
+        let vars = read_vars(&dir.join("host_vars"), &host, warnings);
+        let entry = inv.host_vars.entry(host).or_default();
+        for (k, v) in vars {
+            entry.entry(k).or_insert(v);
+        }
+    }
+}
+
+fn read_vars(dir: &Path, name: &str, warnings: &mut Vec<String>) -> HashMap<String, String> {
+    let mut files = Vec::new();
+    for ext in ["", ".yml", ".yaml"] {
+        let candidate = dir.join(format!("{}{}", name, ext));
+        if candidate.is_file() {
+            files.push(candidate);
+        } else if candidate.is_dir() {
+            if let Ok(entries) = fs::read_dir(&candidate) {
+                let mut inner: Vec<_> = entries.flatten().map(|e| e.path()).collect();
+                inner.sort();
+                files.extend(inner.into_iter().filter(|p| p.is_file()));
+            }
+        }
+    }
+
+    let mut vars = HashMap::new();
+    for file in files {
+        let parsed = fs::read_to_string(&file)
+            .map_err(|e| e.to_string())
+            .and_then(|t| serde_yaml::from_str::<Value>(&t).map_err(|e| e.to_string()));
+        match parsed {
+            Ok(value) => vars.extend(yaml_vars(&value)),
+            Err(e) => warnings.push(format!("{}: {}", file.display(), e)),
+        }
+    }
+    vars
+}
+
+/* =========================
+   INI
+========================= */
+
+fn parse_ini(text: &str, warnings: &mut Vec<String>) -> AnsibleInventory {
+    enum Section {
+        Hosts(String),
+        Vars(String),
+        Children(String),
+    }
+
+    let mut inv = AnsibleInventory::default();
+    let mut section = Section::Hosts("ungrouped".into());
+
+    for (lineno, raw) in text.lines().enumerate() {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            let (name, kind) = header.split_once(':').unwrap_or((header, ""));
+            inv.group(name);
+            section = match kind {
+                "" => Section::Hosts(name.to_string()),
+                "vars" => Section::Vars(name.to_string()),
+                "children" => Section::Children(name.to_string()),
+                other => {
+                    warnings.push(format!("Line {}: unknown section type '{}'", lineno + 1, other));
+                    Section::Hosts(name.to_string())
+                }
+            };
+            continue;
+        }
+
+        match &section {
+            Section::Hosts(group) => {
+                let mut words = split_words(line).into_iter();
+                let Some(pattern) = words.next() else {
+                    continue;
+                };
+                let vars: HashMap<String, String> = words
+                    .filter_map(|w| w.split_once('=').map(|(k, v)| (k.to_string(), v.to_string())))
+                    .collect();
+                match expand_host_pattern(&pattern) {
+                    Ok(hosts) => {
+                        for host in hosts {
+                            inv.add_host(group, &host, vars.clone());
+                        }
+                    }
+                    Err(e) => warnings.push(format!("Line {}: {} in '{}', skipped", lineno + 1, e, pattern)),
+                }
+            }
+            Section::Vars(group) => {
+                if let Some((k, v)) = line.split_once('=') {
+                    let value = split_words(v.trim()).into_iter().next().unwrap_or_default();
+                    inv.group(group).vars.insert(k.trim().to_string(), value);
+                }
+            }
+            Section::Children(group) => {
+                let child = line.to_string();
+                inv.group(&child);
+                let g = inv.group(group);
+                if !g.children.contains(&child) {
+                    g.children.push(child);
+                }
+            }
+        }
+    }
+
+    inv
+}
+
+/// Whitespace separated, single or double quotes group words
+fn split_words(s: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut cur = String::new();
+    let mut quote: Option<char> = None;
+
+    for c in s.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => cur.push(c),
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '#') if cur.is_empty() => break,
+            (None, c) if c.is_whitespace() => {
+                if !cur.is_empty() {
+                    words.push(std::mem::take(&mut cur));
+                }
+            }
+            (None, c) => cur.push(c),
+        }
+    }
+    if !cur.is_empty() {
+        words.push(cur);
+    }
+    words
+}
+
+/// `web[01:03].example.com` -> web01, web02, web03; `db-[a:c]` -> db-a, db-b, db-c.
+/// Ranges that mix numbers and letters, run backwards or expand to more than
+/// `MAX_PATTERN_HOSTS` hosts are an error.
+fn expand_host_pattern(pattern: &str) -> Result<Vec<String>, String> {
+    let (Some(open), Some(close)) = (pattern.find('['), pattern.find(']')) else {
+        return Ok(vec![pattern.to_string()]);
+    };
+    if close < open {
+        return Ok(vec![pattern.to_string()]);
+    }
+
+    let (prefix, range, suffix) = (&pattern[..open], &pattern[open + 1..close], &pattern[close + 1..]);
+    let mut parts = range.split(':');
+    let (Some(start), Some(end)) = (parts.next(), parts.next()) else {
+        return Ok(vec![pattern.to_string()]);
+    };
+    let step: usize = parts.next().and_then(|s| s.parse().ok()).unwrap_or(1).max(1);
+
+    let too_many = || format!("invalid range [{}]: more than {} hosts", range, MAX_PATTERN_HOSTS);
+
+    let items: Vec<String> = if let (Ok(a), Ok(b)) = (start.parse::<u64>(), end.parse::<u64>()) {
+        if b >= a && (b - a) / step as u64 >= MAX_PATTERN_HOSTS as u64 {
+            return Err(too_many());
+        }
+        let width = if start.starts_with('0') { start.len() } else { 0 };
+        (a..=b).step_by(step).map(|n| format!("{:0width$}", n, width = width)).collect()
+    } else if let (Some(a), Some(b)) = (single_char(start), single_char(end)) {
+        (a..=b).step_by(step).map(String::from).collect()
+    } else {
+        return Err(format!("invalid range [{}]", range));
+    };
+    if items.is_empty() {
+        return Err(format!("empty range [{}]", range));
+    }
+
+    let mut hosts = Vec::new();
+    for item in items {
+        hosts.extend(expand_host_pattern(&format!("{}{}{}", prefix, item, suffix))?);
+        if hosts.len() > MAX_PATTERN_HOSTS {
+            return Err(too_many());
+        }
+    }
+    Ok(hosts)
+}
+
+fn single_char(s: &str) -> Option<char> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_alphabetic() => Some(c),
+        _ => None,
+    }
+}
+
+/* =========================
+   YAML
+========================= */
+
+fn parse_yaml(text: &str, warnings: &mut Vec<String>) -> Result<AnsibleInventory, String> {
+    let doc: Value = serde_yaml::from_str(text).map_err(|e| e.to_string())?;
+    let Value::Mapping(top) = doc else {
+        return Err("Inventory must be a mapping of groups".into());
+    };
+
+    let mut inv = AnsibleInventory::default();
+    for (name, body) in &top {
+        if let Some(name) = name.as_str() {
+            parse_yaml_group(&mut inv, name, body, warnings);
+        }
+    }
+    Ok(inv)
+}
+
+fn parse_yaml_group(inv: &mut AnsibleInventory, name: &str, body: &Value, warnings: &mut Vec<String>) {
+    inv.group(name);
+
+    if let Some(Value::Mapping(hosts)) = body.get("hosts") {
+        for (host, vars) in hosts {
+            let Some(pattern) = host.as_str() else {
+                continue;
+            };
+            match expand_host_pattern(pattern) {
+                Ok(expanded) => {
+                    for host in expanded {
+                        inv.add_host(name, &host, yaml_vars(vars));
+                    }
+                }
+                Err(e) => warnings.push(format!("Group '{}': {} in '{}', skipped", name, e, pattern)),
+            }
+        }
+    }
+
+    if let Some(vars) = body.get("vars") {
+        let vars = yaml_vars(vars);
+        inv.group(name).vars.extend(vars);
+    }
+
+    if let Some(Value::Mapping(children)) = body.get("children") {
+        for (child, child_body) in children {
+            let Some(child) = child.as_str() else {
+                continue;
+            };
+            let g = inv.group(name);
+            if !g.children.iter().any(|c| c == child) {
+                g.children.push(child.to_string());
+            }
+            if child == name {
+                warnings.push(format!("Group '{}' lists itself as a child", name));
+                continue;
+            }
+            parse_yaml_group(inv, child, child_body, warnings);
+        }
+    }
+}
+
+/// Scalar vars as strings; vault-encrypted and structured values are skipped
+fn yaml_vars(v: &Value) -> HashMap<String, String> {
+    let Value::Mapping(map) = v else {
+        return HashMap::new();
+    };
+
+    map.iter()
+        .filter_map(|(k, v)| {
+            let value = match v {
+                Value::String(s) => s.clone(),
+                Value::Number(n) => n.to_string(),
+                Value::Bool(b) => b.to_string(),
+                _ => return None,
+            };
+            Some((k.as_str()?.to_string(), value))
+        })
+        .collect()
+}
+
+/* =========================
+   BUILD
+========================= */
+
+fn build_hosts(inv: &AnsibleInventory, warnings: &mut Vec<String>) -> Vec<ImportHost> {
+    // first parent wins, a group below several parents is placed once
+    let mut parent: HashMap<&str, &str> = HashMap::new();
+    for name in &inv.order {
+        for child in &inv.groups[name].children {
+            if IMPLICIT_GROUPS.contains(&name.as_str()) || parent.contains_key(child.as_str()) {
+                continue;
+            }
+            parent.insert(child.as_str(), name.as_str());
+        }
+    }
+
+    let chain = |group: &str| -> Vec<String> {
+        let mut path = vec![group.to_string()];
+        let mut current = group;
+        while let Some(p) = parent.get(current) {
+            if path.iter().any(|g| g == p) {
+                break;
+            }
+            path.push(p.to_string());
+            current = p;
+        }
+        path.reverse();
+        path
+    };
+
+    // host -> groups it is listed in, file order
+    let mut memberships: Vec<(&str, Vec<&str>)> = Vec::new();
+    for name in &inv.order {
+        for host in &inv.groups[name].hosts {
+            match memberships.iter_mut().find(|(h, _)| h == host) {
+                Some((_, groups)) => groups.push(name.as_str()),
+                None => memberships.push((host.as_str(), vec![name.as_str()])),
+            }
+        }
+    }
+
+    let mut hosts = Vec::new();
+    for (host, groups) in memberships {
+        let explicit: Vec<&str> = groups
+            .iter()
+            .copied()
+            .filter(|g| !IMPLICIT_GROUPS.contains(g))
+            .collect();
+
+        // the deepest group is the most specific place for the host
+        let placement = explicit
+            .iter()
+            .map(|g| chain(g))
+            .max_by_key(|path| path.len())
+            .unwrap_or_default();
+
+        if explicit.len() > 1 {
+            warnings.push(format!(
+                "{}: member of {} groups, placed in '{}'",
+                host,
+                explicit.len(),
+                placement.join(" / ")
+            ));
+        }
+
+        // all < parent groups < child groups < host vars
+        let mut var_groups: Vec<Vec<String>> = explicit.iter().map(|g| chain(g)).collect();
+        var_groups.sort_by_key(|path| path.len());
+        let mut vars: HashMap<&str, &str> = HashMap::new();
+        let ordered = std::iter::once("all".to_string()).chain(var_groups.into_iter().flatten());
+        for group in ordered {
+            if let Some(g) = inv.groups.get(&group) {
+                vars.extend(g.vars.iter().map(|(k, v)| (k.as_str(), v.as_str())));
+            }
+        }
+        if let Some(hv) = inv.host_vars.get(host) {
+            vars.extend(hv.iter().map(|(k, v)| (k.as_str(), v.as_str())));
+        }
+
+        let var = |keys: &[&str]| keys.iter().find_map(|k| vars.get(k).map(|v| v.to_string()));
+
+        let port = match var(&["ansible_port", "ansible_ssh_port"]) {
+            Some(p) => p.parse().unwrap_or_else(|_| {
+                warnings.push(format!("{}: invalid ansible_port '{}', using 22", host, p));
+                22
+            }),
+            None => 22,
+        };
+        let identity_file = var(&["ansible_ssh_private_key_file", "ansible_private_key_file"]);
+        let proxy_jump = var(&["ansible_ssh_common_args", "ansible_ssh_extra_args"])
+            .and_then(|args| proxy_jump_from_args(&args));
+
+        hosts.push(ImportHost {
+            name: host.to_string(),
+            host: var(&["ansible_host", "ansible_ssh_host"]).unwrap_or_else(|| host.to_string()),
+            port,
+            username: var(&["ansible_user", "ansible_ssh_user"]).unwrap_or_default(),
+            password: var(&["ansible_password", "ansible_ssh_pass"]),
+            auth_type: if identity_file.is_some() { "key" } else { "password" }.into(),
+            identity_file,
+            proxy_jump,
+            group_path: placement,
+        });
+    }
+
+    hosts
+}
+
+/// `-J bastion` or `-o ProxyJump=bastion` inside ansible_ssh_common_args
+fn proxy_jump_from_args(args: &str) -> Option<String> {
+    let words = split_words(args);
+    let mut iter = words.iter();
+
+    while let Some(word) = iter.next() {
+        if word == "-J" {
+            return iter.next().cloned();
+        }
+        if let Some(jump) = word.strip_prefix("-J") {
+            return Some(jump.to_string());
+        }
+
+        let option = if word == "-o" {
+            iter.next().map(String::as_str)
+        } else {
+            word.strip_prefix("-o")
+        };
+        if let Some((key, value)) = option.and_then(|o| o.split_once('=')) {
+            if key.eq_ignore_ascii_case("ProxyJump") {
+                return Some(value.to_string());
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find<'a>(hosts: &'a [ImportHost], name: &str) -> &'a ImportHost {
+        hosts.iter().find(|h| h.name == name).unwrap()
+    }
+
+    #[test]
+    fn expands_numeric_ranges_with_padding() {
+        let hosts = expand_host_pattern("web[01:10].example.com").unwrap();
+        assert_eq!(hosts.len(), 10);
+        assert_eq!(hosts[0], "web01.example.com");
+        assert_eq!(hosts[9], "web10.example.com");
+
+        assert_eq!(expand_host_pattern("n[8:11]").unwrap(), vec!["n8", "n9", "n10", "n11"]);
+        assert_eq!(expand_host_pattern("n[1:10:3]").unwrap(), vec!["n1", "n4", "n7", "n10"]);
+    }
+
+    #[test]
+    fn expands_letter_and_nested_ranges() {
+        assert_eq!(expand_host_pattern("db-[a:c]").unwrap(), vec!["db-a", "db-b", "db-c"]);
+        assert_eq!(
+            expand_host_pattern("r[1:2]-[x:y]").unwrap(),
+            vec!["r1-x", "r1-y", "r2-x", "r2-y"]
+        );
+        assert_eq!(expand_host_pattern("plain.lan").unwrap(), vec!["plain.lan"]);
+        assert_eq!(expand_host_pattern("odd]name[").unwrap(), vec!["odd]name["]);
+    }
+
+    #[test]
+    fn rejects_invalid_ranges() {
+        assert!(expand_host_pattern("web[10:01]").is_err());
+        assert!(expand_host_pattern("web[c:a]").is_err());
+        assert!(expand_host_pattern("web[1:c]").is_err());
+        assert!(expand_host_pattern("web[1:]").is_err());
+        assert!(expand_host_pattern("web[aa:zz]").is_err());
+    }
+
+    #[test]
+    fn caps_range_expansion() {
+        assert_eq!(expand_host_pattern("web[1:10000]").unwrap().len(), 10_000);
+        assert_eq!(expand_host_pattern("web[0:4000000000:400001]").unwrap().len(), 10_000);
+        assert!(expand_host_pattern("web[0:4000000000:400000]").is_err());
+
+        let err = expand_host_pattern("web[0:4000000000]").unwrap_err();
+        assert_eq!(err, "invalid range [0:4000000000]: more than 10000 hosts");
+        assert!(expand_host_pattern("web[1:10001]").is_err());
+        assert!(expand_host_pattern("r[1:200]-[1:100]").unwrap_err().starts_with("invalid range [1:200]"));
+        assert!(expand_host_pattern(&format!("x{}", "[0:9]".repeat(8))).is_err());
+    }
+
+    #[test]
+    fn ini_sections() {
+        let text = "\
+# comment
+; also a comment
+bastion.lan ansible_user=ops
+
+[web]
+web[1:2].lan ansible_port=2222 # inline comment
+web-extra ansible_host='10.0.0.9'
+
+[db]
+db[a:b] ansible_user=postgres
+
+[prod:children]
+web
+db
+
+[prod:vars]
+ansible_user=deploy
+ansible_ssh_common_args='-o ProxyJump=bastion.lan'
+
+[web:vars]
+ansible_ssh_private_key_file=~/.ssh/web
+
+[broken]
+bad[9:1]
+
+[odd:weird]
+";
+        let mut warnings = Vec::new();
+        let inv = parse_ini(text, &mut warnings);
+        assert_eq!(inv.groups["prod"].children, vec!["web", "db"]);
+        assert_eq!(inv.groups["web"].hosts, vec!["web1.lan", "web2.lan", "web-extra"]);
+        assert_eq!(inv.groups["ungrouped"].hosts, vec!["bastion.lan"]);
+        assert!(inv.groups["broken"].hosts.is_empty());
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].contains("bad[9:1]"));
+        assert!(warnings[1].contains("unknown section type 'weird'"));
+
+        let hosts = build_hosts(&inv, &mut warnings);
+        let web1 = find(&hosts, "web1.lan");
+        assert_eq!(web1.group_path, vec!["prod", "web"]);
+        assert_eq!(web1.port, 2222);
+        assert_eq!(web1.username, "deploy");
+        assert_eq!(web1.identity_file.as_deref(), Some("~/.ssh/web"));
+        assert_eq!(web1.proxy_jump.as_deref(), Some("bastion.lan"));
+
+        assert_eq!(find(&hosts, "web-extra").host, "10.0.0.9");
+        // host vars beat group vars
+        let dba = find(&hosts, "dba");
+        assert_eq!(dba.username, "postgres");
+        assert_eq!(dba.group_path, vec!["prod", "db"]);
+        assert!(dba.identity_file.is_none());
+
+        let bastion = find(&hosts, "bastion.lan");
+        assert!(bastion.group_path.is_empty());
+        assert_eq!(bastion.username, "ops");
+    }
+
+    #[test]
+    fn yaml_groups_children_and_vars() {
+        let text = "
+all:
+  vars:
+    ansible_user: admin
+  children:
+    prod:
+      vars:
+        ansible_port: 2200
+      children:
+        web:
+          hosts:
+            web[01:02]:
+            special:
+              ansible_host: 10.0.0.5
+              ansible_port: 22
+              secret: !vault |
+                abc
+        loop:
+          children:
+            loop:
+    lab:
+      hosts:
+        bad[b:a]:
+        box:
+          ansible_port: nope
+";
+        let mut warnings = Vec::new();
+        let inv = parse_yaml(text, &mut warnings).unwrap();
+        assert_eq!(inv.groups["prod"].children, vec!["web", "loop"]);
+        assert!(!inv.host_vars["special"].contains_key("secret"));
+
+        let hosts = build_hosts(&inv, &mut warnings);
+        let web01 = find(&hosts, "web01");
+        assert_eq!(web01.group_path, vec!["prod", "web"]);
+        assert_eq!(web01.port, 2200);
+        assert_eq!(web01.username, "admin");
+
+        let special = find(&hosts, "special");
+        assert_eq!(special.host, "10.0.0.5");
+        assert_eq!(special.port, 22);
+
+        assert_eq!(find(&hosts, "box").port, 22);
+        assert!(hosts.iter().all(|h| !h.name.starts_with("bad")));
+
+        assert_eq!(warnings.len(), 3);
+        assert!(warnings[0].contains("'loop' lists itself"));
+        assert!(warnings[1].contains("bad[b:a]"));
+        assert!(warnings[2].contains("invalid ansible_port 'nope'"));
+    }
+
+    #[test]
+    fn yaml_must_be_a_mapping() {
+        let mut warnings = Vec::new();
+        assert!(parse_yaml("- a\n- b\n", &mut warnings).is_err());
+        assert!(parse_yaml("all: [unclosed", &mut warnings).is_err());
+    }
+
+    #[test]
+    fn proxy_jump_from_ssh_args() {
+        assert_eq!(proxy_jump_from_args("-J bastion").as_deref(), Some("bastion"));
+        assert_eq!(proxy_jump_from_args("-Jbastion").as_deref(), Some("bastion"));
+        assert_eq!(proxy_jump_from_args("-o ProxyJump=gw:2200").as_deref(), Some("gw:2200"));
+        assert_eq!(proxy_jump_from_args("-oproxyjump=gw").as_deref(), Some("gw"));
+        assert_eq!(proxy_jump_from_args("-o StrictHostKeyChecking=no"), None);
+    }
+}
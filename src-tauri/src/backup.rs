@@ -0,0 +1,326 @@
+use rusqlite::{backup::Progress, Connection, DatabaseName, OpenFlags};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime},
+};
+use tauri::{AppHandle, Manager};
+
+use crate::db::Db;
+use crate::migrations::{latest_version, run_migrations};
+use crate::settings::{get_setting, set_setting};
+
+/* =========================
+   CONFIG
+========================= */
+
+const SETTINGS_KEY: &str = "snapshots";
+const SNAPSHOT_PREFIX: &str = "snapshot-";
+/// How often the background thread checks whether a snapshot is due
+const SNAPSHOT_CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct SnapshotConfig {
+    pub enabled: bool,
+    /// Rolling snapshots kept in `<app data dir>/snapshots`, oldest removed first
+    pub keep: usize,
+    pub interval_hours: u64,
+}
+
+impl Default for SnapshotConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            keep: 10,
+            interval_hours: 24,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct BackupInfo {
+    pub path: String,
+    pub size: u64,
+    /// Unix seconds
+    pub modified: i64,
+}
+
+/* =========================
+   TAURI COMMANDS
+========================= */
+
+/// Online copy of the live database, safe while the app keeps using it
+#[tauri::command]
+pub fn backup_database(path: String, db: tauri::State<Db>) -> Result<BackupInfo, String> {
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    write_backup(&conn, Path::new(&path))
+}
+
+/// Validates `path`, snapshots the current database, then replaces it with
+/// the backup. Backups from older versions are migrated forward.
+#[tauri::command]
+pub fn restore_database(path: String, app: AppHandle) -> Result<(), String> {
+    let path = Path::new(&path);
+    validate_backup(path)?;
+
+    take_snapshot(&app, "pre-restore")?;
+
+    let db = app.state::<Db>();
+    let mut conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    conn.restore(DatabaseName::Main, path, None::<fn(Progress)>)
+        .map_err(|e| format!("Restore failed: {}", e))?;
+
+    run_migrations(&mut conn, &backups_dir(&app)?)?;
+    println!("[Backup] Restored database from {}", path.display());
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_snapshots(app: AppHandle) -> Result<Vec<BackupInfo>, String> {
+    let mut snapshots = snapshot_files(&snapshots_dir(&app)?)?;
+    snapshots.reverse();
+    snapshots.iter().map(|p| backup_info(p)).collect()
+}
+
+#[tauri::command]
+pub fn create_snapshot(app: AppHandle) -> Result<BackupInfo, String> {
+    take_snapshot(&app, "manual")
+}
+
+#[tauri::command]
+pub fn get_snapshot_config(db: tauri::State<Db>) -> Result<SnapshotConfig, String> {
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    Ok(get_setting(&conn, SETTINGS_KEY)?.unwrap_or_default())
+}
+
+#[tauri::command]
+pub fn set_snapshot_config(config: SnapshotConfig, db: tauri::State<Db>) -> Result<(), String> {
+    if config.keep == 0 {
+        return Err("At least one snapshot must be kept".into());
+    }
+    if config.interval_hours == 0 {
+        return Err("Snapshot interval must be at least one hour".into());
+    }
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    set_setting(&conn, SETTINGS_KEY, &config)
+}
+
+/* =========================
+   SNAPSHOTS
+========================= */
+
+/// Writes a snapshot and drops the oldest ones beyond the configured count.
+/// Also used before destructive operations such as a replacing import.
+pub fn take_snapshot(app: &AppHandle, reason: &str) -> Result<BackupInfo, String> {
+    let dir = snapshots_dir(app)?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    // named under the lock, so two snapshots cannot pick the same free name
+    let db = app.state::<Db>();
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    let path = snapshot_path(&dir, &chrono::Local::now().format("%Y%m%d-%H%M%S%3f").to_string(), reason);
+    let info = write_backup(&conn, &path)?;
+    let config: SnapshotConfig = get_setting(&conn, SETTINGS_KEY)?.unwrap_or_default();
+    drop(conn);
+
+    let snapshots = snapshot_files(&dir)?;
+    let excess = snapshots.len().saturating_sub(config.keep.max(1));
+    for old in &snapshots[..excess] {
+        if let Err(e) = fs::remove_file(old) {
+            println!("[Backup] Cannot remove old snapshot {}: {}", old.display(), e);
+        }
+    }
+
+    Ok(info)
+}
+
+/// Checks periodically and snapshots once the newest snapshot is older than
+/// the configured interval; the first check runs right at launch
+pub fn start_auto_snapshots(app: &AppHandle) {
+    let app = app.clone();
+    thread::spawn(move || loop {
+        if let Err(e) = auto_snapshot_if_due(&app) {
+            println!("[Backup] Automatic snapshot failed: {}", e);
+        }
+        thread::sleep(SNAPSHOT_CHECK_INTERVAL);
+    });
+}
+
+fn auto_snapshot_if_due(app: &AppHandle) -> Result<(), String> {
+    let config: SnapshotConfig = {
+        let db = app.state::<Db>();
+        let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+        get_setting(&conn, SETTINGS_KEY)?.unwrap_or_default()
+    };
+    if !config.enabled {
+        return Ok(());
+    }
+
+    let interval = Duration::from_secs(config.interval_hours * 3600);
+    let newest = snapshot_files(&snapshots_dir(app)?)?
+        .last()
+        .and_then(|p| fs::metadata(p).and_then(|m| m.modified()).ok());
+    let due = match newest {
+        Some(t) => SystemTime::now().duration_since(t).unwrap_or_default() >= interval,
+        None => true,
+    };
+
+    if due {
+        let info = take_snapshot(app, "auto")?;
+        println!("[Backup] Automatic snapshot written to {}", info.path);
+    }
+    Ok(())
+}
+
+/// Oldest first; names embed the timestamp so they sort chronologically
+fn snapshot_files(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| e.to_string())?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            let name = p.file_name().unwrap_or_default().to_string_lossy();
+            name.starts_with(SNAPSHOT_PREFIX) && name.ends_with(".db")
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// `snapshot-<timestamp>-<reason>.db`, with a counter when a snapshot of the
+/// same reason was taken in the same millisecond
+fn snapshot_path(dir: &Path, timestamp: &str, reason: &str) -> PathBuf {
+    (1..)
+        .map(|n| {
+            let counter = if n == 1 { String::new() } else { format!("-{}", n) };
+            dir.join(format!("{}{}-{}{}.db", SNAPSHOT_PREFIX, timestamp, reason, counter))
+        })
+        .find(|p| !p.exists())
+        .unwrap()
+}
+
+fn snapshots_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(app.path().app_data_dir().map_err(|e| e.to_string())?.join("snapshots"))
+}
+
+fn backups_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(app.path().app_data_dir().map_err(|e| e.to_string())?.join("backups"))
+}
+
+/* =========================
+   HELPERS
+========================= */
+
+/// Copies into a temporary file first so a failed backup never leaves a
+/// truncated file under the final name
+fn write_backup(conn: &Connection, path: &Path) -> Result<BackupInfo, String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let mut tmp = path.as_os_str().to_os_string();
+    tmp.push(".partial");
+    let tmp = PathBuf::from(tmp);
+    let _ = fs::remove_file(&tmp);
+
+    conn.backup(DatabaseName::Main, &tmp, None)
+        .map_err(|e| format!("Backup failed: {}", e))?;
+    fs::rename(&tmp, path).map_err(|e| e.to_string())?;
+
+    backup_info(path)
+}
+
+/// A usable backup is an intact SQLite file with the NetHopper tables and a
+/// schema version this build can migrate from
+fn validate_backup(path: &Path) -> Result<(), String> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| format!("Not a readable database: {}", e))?;
+
+    let integrity: String = conn
+        .query_row("PRAGMA integrity_check", [], |r| r.get(0))
+        .map_err(|e| format!("Not a valid database: {}", e))?;
+    if integrity != "ok" {
+        return Err(format!("Backup is corrupted: {}", integrity));
+    }
+
+    for table in ["groups", "hosts"] {
+        let found: bool = conn
+            .query_row(
+                "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?)",
+                [table],
+                |r| r.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        if !found {
+            return Err(format!("Not a NetHopper backup (missing table '{}')", table));
+        }
+    }
+
+    let version: i64 = conn
+        .query_row("PRAGMA user_version", [], |r| r.get(0))
+        .map_err(|e| e.to_string())?;
+    if version > latest_version() {
+        return Err(format!(
+            "Backup schema version {} is newer than this app supports ({})",
+            version,
+            latest_version()
+        ));
+    }
+
+    Ok(())
+}
+
+fn backup_info(path: &Path) -> Result<BackupInfo, String> {
+    let meta = fs::metadata(path).map_err(|e| e.to_string())?;
+    let modified = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_secs() as i64);
+
+    Ok(BackupInfo {
+        path: path.to_string_lossy().to_string(),
+        size: meta.len(),
+        modified,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshots_in_the_same_millisecond_get_distinct_names() {
+        let dir = std::env::temp_dir().join(format!("nethopper-snapshots-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut names = Vec::new();
+        for _ in 0..3 {
+            let path = snapshot_path(&dir, "20261018-201530123", "manual");
+            fs::write(&path, "").unwrap();
+            names.push(path.file_name().unwrap().to_string_lossy().to_string());
+        }
+        let other = snapshot_path(&dir, "20261018-201530123", "pre-restore");
+        let listed = snapshot_files(&dir).unwrap().len();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            names,
+            [
+                "snapshot-20261018-201530123-manual.db",
+                "snapshot-20261018-201530123-manual-2.db",
+                "snapshot-20261018-201530123-manual-3.db",
+            ]
+        );
+        assert!(other.ends_with("snapshot-20261018-201530123-pre-restore.db"));
+        assert_eq!(listed, 3);
+    }
+}
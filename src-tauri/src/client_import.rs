@@ -0,0 +1,702 @@
+use serde::Deserialize;
+use serde_json::Value;
+use std::{collections::HashMap, fs, path::Path};
+use tauri::{AppHandle, Manager};
+
+use crate::db::Db;
+use crate::import::{apply_import, preview_import, DuplicateMode, ImportHost, ImportPreview, ImportResult};
+
+/* =========================
+   SOURCES
+========================= */
+
+/// Session export of another SSH client
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum ClientSource {
+    /// Registry export (.reg) of `HKCU\Software\SimonTatham\PuTTY\Sessions`
+    Putty,
+    /// .mxtsessions file
+    Mobaxterm,
+    /// CSV or JSON export, picked by file extension
+    Termius,
+}
+
+/* =========================
+   TAURI COMMANDS
+========================= */
+
+#[tauri::command]
+pub fn preview_client_import(
+    source: ClientSource,
+    path: String,
+    group_id: Option<i64>,
+    app: AppHandle,
+) -> Result<ImportPreview, String> {
+    let (hosts, warnings) = parse_client_file(source, Path::new(&path))?;
+
+    let db = app.state::<Db>();
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    preview_import(&conn, hosts, group_id, warnings)
+}
+
+/// Folders of the source client become nested groups below `group_id`
+#[tauri::command]
+pub fn import_client_sessions(
+    source: ClientSource,
+    path: String,
+    group_id: Option<i64>,
+    duplicates: Option<DuplicateMode>,
+    app: AppHandle,
+) -> Result<ImportResult, String> {
+    let (hosts, warnings) = parse_client_file(source, Path::new(&path))?;
+
+    let db = app.state::<Db>();
+    let mut conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    apply_import(&mut conn, hosts, group_id, duplicates.unwrap_or_default(), warnings)
+}
+
+fn parse_client_file(source: ClientSource, path: &Path) -> Result<(Vec<ImportHost>, Vec<String>), String> {
+    let bytes = fs::read(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    let text = decode_text(&bytes);
+    let mut warnings = Vec::new();
+
+    let hosts = match source {
+        ClientSource::Putty => parse_putty(&text, &mut warnings),
+        ClientSource::Mobaxterm => parse_mobaxterm(&text, &mut warnings),
+        ClientSource::Termius => {
+            let is_json = path
+                .extension()
+                .is_some_and(|e| e.eq_ignore_ascii_case("json"));
+            if is_json {
+                parse_termius_json(&text, &mut warnings)?
+            } else {
+                parse_termius_csv(&text, &mut warnings)?
+            }
+        }
+    };
+
+    Ok((hosts, warnings))
+}
+
+/* =========================
+   PUTTY
+========================= */
+
+const PUTTY_SESSIONS_KEY: &str = "\\Software\\SimonTatham\\PuTTY\\Sessions\\";
+
+fn parse_putty(text: &str, warnings: &mut Vec<String>) -> Vec<ImportHost> {
+    let mut sessions: Vec<(String, HashMap<String, String>)> = Vec::new();
+
+    for line in text.lines() {
+        let line = line.trim();
+        if let Some(key) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            match key.find(PUTTY_SESSIONS_KEY) {
+                Some(i) => sessions.push((
+                    url_decode(&key[i + PUTTY_SESSIONS_KEY.len()..]),
+                    HashMap::new(),
+                )),
+                // keys outside Sessions end the current session
+                None => sessions.push((String::new(), HashMap::new())),
+            }
+            continue;
+        }
+
+        let Some((name, value)) = line.split_once('=') else {
+            continue;
+        };
+        if let Some((_, values)) = sessions.last_mut() {
+            values.insert(name.trim_matches('"').to_string(), reg_value(value));
+        }
+    }
+
+    let mut hosts = Vec::new();
+    for (name, values) in sessions {
+        if name.is_empty() || name == "Default Settings" {
+            continue;
+        }
+        if values.get("Protocol").is_some_and(|p| p != "ssh") {
+            warnings.push(format!("{}: not an SSH session, skipped", name));
+            continue;
+        }
+
+        let raw_host = values.get("HostName").cloned().unwrap_or_default();
+        if raw_host.is_empty() {
+            warnings.push(format!("{}: no host name, skipped", name));
+            continue;
+        }
+        // PuTTY accepts user@host in the host name field
+        let (user_in_host, host) = match raw_host.split_once('@') {
+            Some((u, h)) => (Some(u.to_string()), h.to_string()),
+            None => (None, raw_host),
+        };
+
+        // PuTTY itself has no folders; session managers keep them in PsmPath
+        // or as "folder/session" names
+        let (group_path, label) = match values.get("PsmPath").filter(|p| !p.is_empty()) {
+            Some(folder) => (split_path(folder, &['\\', '/']), name.clone()),
+            None => {
+                let mut parts = split_path(&name, &['/', '\\']);
+                let label = parts.pop().unwrap_or_else(|| name.clone());
+                (parts, label)
+            }
+        };
+
+        let identity_file = values.get("PublicKeyFile").filter(|k| !k.is_empty()).cloned();
+
+        hosts.push(ImportHost {
+            name: label,
+            host,
+            port: values
+                .get("PortNumber")
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(22),
+            username: values
+                .get("UserName")
+                .filter(|u| !u.is_empty())
+                .cloned()
+                .or(user_in_host)
+                .unwrap_or_default(),
+            password: None,
+            auth_type: if identity_file.is_some() { "key" } else { "password" }.into(),
+            identity_file,
+            proxy_jump: None,
+            group_path,
+        });
+    }
+
+    hosts
+}
+
+/// `"string"` or `dword:00000016`
+fn reg_value(raw: &str) -> String {
+    let raw = raw.trim();
+    if let Some(hex) = raw.strip_prefix("dword:") {
+        return u32::from_str_radix(hex, 16)
+            .map(|v| v.to_string())
+            .unwrap_or_default();
+    }
+    raw.trim_matches('"').replace("\\\\", "\\").replace("\\\"", "\"")
+}
+
+/* =========================
+   MOBAXTERM
+========================= */
+
+/// Field positions inside a MobaXterm SSH bookmark (`%` separated)
+const MOBA_TYPE_SSH: &str = "0";
+const MOBA_HOST: usize = 1;
+const MOBA_PORT: usize = 2;
+const MOBA_USER: usize = 3;
+const MOBA_GATEWAY_HOST: usize = 7;
+const MOBA_GATEWAY_PORT: usize = 8;
+const MOBA_GATEWAY_USER: usize = 9;
+const MOBA_KEY_FILE: usize = 14;
+
+fn parse_mobaxterm(text: &str, warnings: &mut Vec<String>) -> Vec<ImportHost> {
+    let mut hosts = Vec::new();
+    let mut folder: Vec<String> = Vec::new();
+    let mut in_bookmarks = false;
+
+    for line in text.lines() {
+        let line = line.trim();
+        if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            in_bookmarks = section.starts_with("Bookmarks");
+            folder.clear();
+            continue;
+        }
+        if !in_bookmarks {
+            continue;
+        }
+
+        let Some((name, value)) = line.split_once('=') else {
+            continue;
+        };
+        match name {
+            "SubRep" => folder = split_path(value, &['\\']),
+            "ImgNum" => {}
+            _ => {
+                // " #109#0%host%22%user%..." -> icon, then the session settings
+                let Some(settings) = value.trim().splitn(3, '#').nth(2) else {
+                    continue;
+                };
+                let fields: Vec<&str> = settings.split('#').next().unwrap_or("").split('%').collect();
+                let field = |i: usize| fields.get(i).map(|f| f.trim()).unwrap_or("");
+
+                if field(0) != MOBA_TYPE_SSH {
+                    warnings.push(format!("{}: not an SSH session, skipped", name));
+                    continue;
+                }
+                if field(MOBA_HOST).is_empty() {
+                    warnings.push(format!("{}: no host name, skipped", name));
+                    continue;
+                }
+
+                let proxy_jump = match field(MOBA_GATEWAY_HOST) {
+                    "" => None,
+                    gw => {
+                        let mut jump = gw.to_string();
+                        if !field(MOBA_GATEWAY_USER).is_empty() {
+                            jump = format!("{}@{}", field(MOBA_GATEWAY_USER), jump);
+                        }
+                        if !matches!(field(MOBA_GATEWAY_PORT), "" | "22") {
+                            jump = format!("{}:{}", jump, field(MOBA_GATEWAY_PORT));
+                        }
+                        Some(jump)
+                    }
+                };
+                let identity_file = Some(field(MOBA_KEY_FILE))
+                    .filter(|k| !k.is_empty())
+                    .map(|k| k.replace("_ProfileDir_", "~"));
+
+                hosts.push(ImportHost {
+                    name: name.trim().to_string(),
+                    host: field(MOBA_HOST).to_string(),
+                    port: field(MOBA_PORT).parse().unwrap_or(22),
+                    username: field(MOBA_USER).to_string(),
+                    password: None,
+                    auth_type: if identity_file.is_some() { "key" } else { "password" }.into(),
+                    identity_file,
+                    proxy_jump,
+                    group_path: folder.clone(),
+                });
+            }
+        }
+    }
+
+    hosts
+}
+
+/* =========================
+   TERMIUS
+========================= */
+
+/// Column names differ between Termius versions, first match wins
+const TERMIUS_NAME: &[&str] = &["label", "name", "alias"];
+const TERMIUS_HOST: &[&str] = &["hostname", "address", "host", "ip", "hostname/ip"];
+const TERMIUS_PORT: &[&str] = &["port", "ssh_port"];
+const TERMIUS_USER: &[&str] = &["username", "user", "ssh_username"];
+const TERMIUS_PASSWORD: &[&str] = &["password", "ssh_password"];
+const TERMIUS_GROUP: &[&str] = &["group", "groups", "folder", "group_path"];
+
+fn parse_termius_csv(text: &str, warnings: &mut Vec<String>) -> Result<Vec<ImportHost>, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(text.as_bytes());
+
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|h| h.trim().to_lowercase())
+        .collect();
+    let column = |names: &[&str]| names.iter().find_map(|n| headers.iter().position(|h| h == n));
+
+    let host_col = column(TERMIUS_HOST).ok_or("No host name column in CSV")?;
+    let name_col = column(TERMIUS_NAME);
+    let port_col = column(TERMIUS_PORT);
+    let user_col = column(TERMIUS_USER);
+    let password_col = column(TERMIUS_PASSWORD);
+    let group_col = column(TERMIUS_GROUP);
+
+    let mut hosts = Vec::new();
+    for (i, record) in reader.records().enumerate() {
+        let record = match record {
+            Ok(r) => r,
+            Err(e) => {
+                warnings.push(format!("Row {}: {}", i + 2, e));
+                continue;
+            }
+        };
+        let get = |col: Option<usize>| {
+            col.and_then(|c| record.get(c))
+                .map(|v| v.trim().to_string())
+                .unwrap_or_default()
+        };
+
+        let host = get(Some(host_col));
+        if host.is_empty() {
+            warnings.push(format!("Row {}: no host name, skipped", i + 2));
+            continue;
+        }
+        let name = Some(get(name_col)).filter(|n| !n.is_empty()).unwrap_or_else(|| host.clone());
+
+        hosts.push(ImportHost {
+            name,
+            port: get(port_col).parse().unwrap_or(22),
+            username: get(user_col),
+            password: Some(get(password_col)).filter(|p| !p.is_empty()),
+            auth_type: "password".into(),
+            identity_file: None,
+            proxy_jump: None,
+            group_path: split_path(&get(group_col), &['/', '>', '\\']),
+            host,
+        });
+    }
+
+    Ok(hosts)
+}
+
+/// Accepts a plain host array or `{ "hosts": [...], "groups": [...] }` where
+/// hosts reference groups by id and groups reference their parent
+fn parse_termius_json(text: &str, warnings: &mut Vec<String>) -> Result<Vec<ImportHost>, String> {
+    let doc: Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+
+    let (host_values, group_values) = match &doc {
+        Value::Array(hosts) => (hosts.clone(), Vec::new()),
+        Value::Object(obj) => (
+            obj.get("hosts").and_then(Value::as_array).cloned().unwrap_or_default(),
+            obj.get("groups").and_then(Value::as_array).cloned().unwrap_or_default(),
+        ),
+        _ => return Err("Unrecognized Termius export".into()),
+    };
+
+    // group id -> (label, parent id)
+    let groups: HashMap<String, (String, Option<String>)> = group_values
+        .iter()
+        .filter_map(|g| {
+            let id = json_id(g.get("id")?)?;
+            let label = json_str(g, TERMIUS_NAME)?;
+            let parent = ["parent_group", "parent_group_id", "parent"]
+                .iter()
+                .find_map(|k| g.get(*k).and_then(json_id));
+            Some((id, (label, parent)))
+        })
+        .collect();
+
+    let mut hosts = Vec::new();
+    for (i, h) in host_values.iter().enumerate() {
+        // connection settings sit either on the host or in its ssh_config
+        let ssh = h.get("ssh_config").unwrap_or(h);
+        let pick = |keys: &[&str]| json_str(h, keys).or_else(|| json_str(ssh, keys));
+
+        let Some(host) = pick(TERMIUS_HOST) else {
+            warnings.push(format!("Host #{}: no address, skipped", i + 1));
+            continue;
+        };
+
+        let group_path = match h.get("group") {
+            Some(Value::String(path)) if !groups.contains_key(path) => split_path(path, &['/', '>']),
+            Some(g) => json_id(g).map(|id| group_chain(&groups, &id)).unwrap_or_default(),
+            None => Vec::new(),
+        };
+
+        hosts.push(ImportHost {
+            name: pick(TERMIUS_NAME).unwrap_or_else(|| host.clone()),
+            port: pick(TERMIUS_PORT).and_then(|p| p.parse().ok()).unwrap_or(22),
+            username: pick(TERMIUS_USER).unwrap_or_default(),
+            password: pick(TERMIUS_PASSWORD),
+            auth_type: "password".into(),
+            identity_file: None,
+            proxy_jump: None,
+            group_path,
+            host,
+        });
+    }
+
+    Ok(hosts)
+}
+
+/// Outermost group first; stops on unknown ids and on cycles
+fn group_chain(groups: &HashMap<String, (String, Option<String>)>, id: &str) -> Vec<String> {
+    let mut path = Vec::new();
+    let mut seen = Vec::new();
+    let mut current = Some(id.to_string());
+
+    while let Some(id) = current.take() {
+        if seen.contains(&id) {
+            break;
+        }
+        let Some((label, parent)) = groups.get(&id) else {
+            break;
+        };
+        path.push(label.clone());
+        seen.push(id);
+        current = parent.clone();
+    }
+
+    path.reverse();
+    path
+}
+
+/// First non-empty string or number under any of `keys`
+fn json_str(v: &Value, keys: &[&str]) -> Option<String> {
+    keys.iter().find_map(|k| match v.get(*k)? {
+        Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    })
+}
+
+/// Ids are numbers or strings, or objects carrying an `id`
+fn json_id(v: &Value) -> Option<String> {
+    match v {
+        Value::Number(n) => Some(n.to_string()),
+        Value::String(s) if !s.is_empty() => Some(s.clone()),
+        Value::Object(o) => o.get("id").and_then(json_id),
+        _ => None,
+    }
+}
+
+/* =========================
+   HELPERS
+========================= */
+
+/// Registry exports are UTF-16LE with a BOM, the rest UTF-8 or Windows-1252
+fn decode_text(bytes: &[u8]) -> String {
+    if let Some(rest) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+        let units: Vec<u16> = rest
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        return String::from_utf16_lossy(&units);
+    }
+    let bytes = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(bytes);
+    match std::str::from_utf8(bytes) {
+        Ok(s) => s.to_string(),
+        // Latin-1 covers the printable part of Windows-1252
+        Err(_) => bytes.iter().map(|&b| b as char).collect(),
+    }
+}
+
+fn split_path(path: &str, separators: &[char]) -> Vec<String> {
+    path.split(|c| separators.contains(&c))
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// PuTTY escapes session names like URLs (`My%20Server`)
+fn url_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            if let Some(b) = s
+                .get(i + 1..i + 3)
+                .and_then(|h| u8::from_str_radix(h, 16).ok())
+            {
+                out.push(b);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&out).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find<'a>(hosts: &'a [ImportHost], name: &str) -> &'a ImportHost {
+        hosts.iter().find(|h| h.name == name).unwrap()
+    }
+
+    #[test]
+    fn putty_registry_export() {
+        let text = r#"Windows Registry Editor Version 5.00
+
+[HKEY_CURRENT_USER\Software\SimonTatham\PuTTY\Sessions\Default%20Settings]
+"HostName"="ignored"
+
+[HKEY_CURRENT_USER\Software\SimonTatham\PuTTY\Sessions\Prod%2Fweb%2001]
+"HostName"="deploy@web01.example.com"
+"PortNumber"=dword:00000016
+"Protocol"="ssh"
+"PublicKeyFile"="C:\\Users\\me\\.ssh\\web.ppk"
+
+[HKEY_CURRENT_USER\Software\SimonTatham\PuTTY\Sessions\db]
+"HostName"="db.lan"
+"UserName"="admin"
+"PortNumber"=dword:000008ae
+"PsmPath"="Data\\Postgres"
+
+[HKEY_CURRENT_USER\Software\SimonTatham\PuTTY\Sessions\router]
+"HostName"="10.0.0.1"
+"Protocol"="telnet"
+
+[HKEY_CURRENT_USER\Software\SimonTatham\PuTTY\Sessions\empty]
+"UserName"="nobody"
+
+[HKEY_CURRENT_USER\Software\SimonTatham\PuTTY\SshHostKeys]
+"HostName"="not-a-session"
+"#;
+        let mut warnings = Vec::new();
+        let hosts = parse_putty(text, &mut warnings);
+
+        assert_eq!(hosts.len(), 2);
+        let web = find(&hosts, "web 01");
+        assert_eq!(web.host, "web01.example.com");
+        assert_eq!(web.username, "deploy");
+        assert_eq!(web.port, 22);
+        assert_eq!(web.group_path, vec!["Prod"]);
+        assert_eq!(web.identity_file.as_deref(), Some(r"C:\Users\me\.ssh\web.ppk"));
+        assert_eq!(web.auth_type, "key");
+
+        let db = find(&hosts, "db");
+        assert_eq!(db.port, 2222);
+        assert_eq!(db.username, "admin");
+        assert_eq!(db.group_path, vec!["Data", "Postgres"]);
+        assert_eq!(db.auth_type, "password");
+
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].starts_with("router: not an SSH session"));
+        assert!(warnings[1].starts_with("empty: no host name"));
+    }
+
+    #[test]
+    fn putty_malformed_lines() {
+        let text = "[HKEY_CURRENT_USER\\Software\\SimonTatham\\PuTTY\\Sessions\\box\n\
+                    \"HostName\"=\"before.lan\"\n\
+                    [HKEY_CURRENT_USER\\Software\\SimonTatham\\PuTTY\\Sessions\\box]\n\
+                    garbage without equals\n\
+                    \"HostName\"=\"box.lan\"\n\
+                    \"PortNumber\"=dword:zzzz\n";
+        let mut warnings = Vec::new();
+        let hosts = parse_putty(text, &mut warnings);
+
+        assert_eq!(hosts.len(), 1);
+        assert_eq!(hosts[0].host, "box.lan");
+        assert_eq!(hosts[0].port, 22);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn registry_values_and_names() {
+        assert_eq!(reg_value("dword:00000016"), "22");
+        assert_eq!(reg_value("dword:nope"), "");
+        assert_eq!(reg_value(r#""C:\\keys\\a \"b\".ppk""#), r#"C:\keys\a "b".ppk"#);
+        assert_eq!(url_decode("My%20Server"), "My Server");
+        assert_eq!(url_decode("100%"), "100%");
+        assert_eq!(url_decode("%zzok"), "%zzok");
+    }
+
+    #[test]
+    fn mobaxterm_bookmarks() {
+        let text = "[Bookmarks]\n\
+                    SubRep=\n\
+                    ImgNum=42\n\
+                    web= #109#0%web.lan%2222%admin%%-1%-1%bastion%2200%jump%%%%%_ProfileDir_\\.ssh\\id_rsa%0#MobaFont%10%0#0# #-1\n\
+                    [Bookmarks_1]\n\
+                    SubRep=Prod\\DB\n\
+                    ImgNum=41\n\
+                    db= #109#0%db.lan%%root%%-1%-1%gw%22%%#MobaFont#0\n\
+                    rdp= #91#4%win.lan%3389%%#MobaFont#0\n\
+                    nohost= #109#0%%22%root#MobaFont#0\n\
+                    broken=no hashes here\n\
+                    [MiscSettings]\n\
+                    other= #109#0%misc.lan%22%x\n";
+        let mut warnings = Vec::new();
+        let hosts = parse_mobaxterm(text, &mut warnings);
+
+        assert_eq!(hosts.len(), 2);
+        let web = find(&hosts, "web");
+        assert_eq!(web.host, "web.lan");
+        assert_eq!(web.port, 2222);
+        assert_eq!(web.username, "admin");
+        assert_eq!(web.proxy_jump.as_deref(), Some("jump@bastion:2200"));
+        assert_eq!(web.identity_file.as_deref(), Some("~\\.ssh\\id_rsa"));
+        assert!(web.group_path.is_empty());
+
+        let db = find(&hosts, "db");
+        assert_eq!(db.port, 22);
+        assert_eq!(db.proxy_jump.as_deref(), Some("gw"));
+        assert_eq!(db.group_path, vec!["Prod", "DB"]);
+        assert!(db.identity_file.is_none());
+
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].starts_with("rdp: not an SSH session"));
+        assert!(warnings[1].starts_with("nohost: no host name"));
+    }
+
+    #[test]
+    fn termius_csv() {
+        let text = "Label,Hostname,Port,Username,Password,Group\n\
+                    web,web.lan,2222,deploy,secret,Prod/Web\n\
+                    ,10.0.0.5,,root,,\n\
+                    missing,,22,root,,\n\
+                    short,short.lan\n";
+        let mut warnings = Vec::new();
+        let hosts = parse_termius_csv(text, &mut warnings).unwrap();
+
+        assert_eq!(hosts.len(), 3);
+        let web = find(&hosts, "web");
+        assert_eq!(web.port, 2222);
+        assert_eq!(web.password.as_deref(), Some("secret"));
+        assert_eq!(web.group_path, vec!["Prod", "Web"]);
+
+        let unnamed = find(&hosts, "10.0.0.5");
+        assert_eq!(unnamed.port, 22);
+        assert!(unnamed.password.is_none());
+
+        assert_eq!(find(&hosts, "short").username, "");
+        assert_eq!(warnings, vec!["Row 4: no host name, skipped".to_string()]);
+    }
+
+    #[test]
+    fn termius_csv_without_host_column() {
+        let mut warnings = Vec::new();
+        let err = parse_termius_csv("Label,Port\nweb,22\n", &mut warnings).err();
+        assert_eq!(err.as_deref(), Some("No host name column in CSV"));
+    }
+
+    #[test]
+    fn termius_json_with_groups() {
+        let text = r#"{
+            "groups": [
+                { "id": 1, "label": "Prod" },
+                { "id": "2", "label": "Web", "parent_group": { "id": 1 } },
+                { "id": 3, "label": "Loop A", "parent_group": 4 },
+                { "id": 4, "label": "Loop B", "parent_group": 3 }
+            ],
+            "hosts": [
+                { "label": "web", "address": "web.lan", "group": { "id": 2 },
+                  "ssh_config": { "port": 2222, "username": "deploy" } },
+                { "address": "plain.lan", "group": "Lab / Rack 1" },
+                { "label": "loop", "address": "loop.lan", "group": 3 },
+                { "label": "nothing", "group": 1 }
+            ]
+        }"#;
+        let mut warnings = Vec::new();
+        let hosts = parse_termius_json(text, &mut warnings).unwrap();
+
+        assert_eq!(hosts.len(), 3);
+        let web = find(&hosts, "web");
+        assert_eq!(web.port, 2222);
+        assert_eq!(web.username, "deploy");
+        assert_eq!(web.group_path, vec!["Prod", "Web"]);
+
+        assert_eq!(find(&hosts, "plain.lan").group_path, vec!["Lab", "Rack 1"]);
+        assert_eq!(find(&hosts, "loop").group_path, vec!["Loop B", "Loop A"]);
+        assert_eq!(warnings, vec!["Host #4: no address, skipped".to_string()]);
+    }
+
+    #[test]
+    fn termius_json_rejects_other_documents() {
+        let mut warnings = Vec::new();
+        assert!(parse_termius_json("[{\"address\": \"a.lan\"}]", &mut warnings).is_ok());
+        assert_eq!(
+            parse_termius_json("42", &mut warnings).err().as_deref(),
+            Some("Unrecognized Termius export")
+        );
+        assert!(parse_termius_json("{ not json", &mut warnings).is_err());
+    }
+
+    #[test]
+    fn decodes_registry_and_legacy_encodings() {
+        let utf16: Vec<u8> = [0xFF, 0xFE]
+            .into_iter()
+            .chain("[a]".encode_utf16().flat_map(u16::to_le_bytes))
+            .collect();
+        assert_eq!(decode_text(&utf16), "[a]");
+        assert_eq!(decode_text(b"\xEF\xBB\xBFhost"), "host");
+        assert_eq!(decode_text(b"caf\xE9"), "café");
+    }
+}
@@ -1,4 +1,7 @@
 use crate::db::Db;
+use crate::trash::{trash_group, trash_host};
+use crate::tree::ensure_valid_parent;
+use crate::validation::{ensure_group, ensure_host, validate_name, CrudError, CrudErrorCode, HostInput};
 use serde::Serialize;
 /* =========================
 MODELS
@@ -11,6 +14,7 @@ pub struct Group {
     pub id: i64,
     pub name: String,
     pub parent_id: Option<i64>,
+    pub favorite: bool,
 }
 
 #[derive(Serialize)]
@@ -22,6 +26,7 @@ pub struct Host {
     pub username: String,
     pub auth_type: String,
     pub group_id: Option<i64>,
+    pub favorite: bool,
 }
 
 #[derive(serde::Serialize)]
@@ -67,11 +72,15 @@ pub fn list_groups_by_parent(
 
     let (sql, params): (&str, Vec<i64>) = match parent_id {
         Some(id) => (
-            "SELECT id, name, parent_id FROM groups WHERE parent_id = ?",
+            "SELECT id, name, parent_id, favorite FROM groups
+             WHERE parent_id = ? AND deleted_at IS NULL
+             ORDER BY sort_order, name COLLATE NOCASE, id",
             vec![id],
         ),
         None => (
-            "SELECT id, name, parent_id FROM groups WHERE parent_id IS NULL",
+            "SELECT id, name, parent_id, favorite FROM groups
+             WHERE parent_id IS NULL AND deleted_at IS NULL
+             ORDER BY sort_order, name COLLATE NOCASE, id",
             vec![],
         ),
     };
@@ -84,6 +93,7 @@ pub fn list_groups_by_parent(
                 id: r.get(0)?,
                 name: r.get(1)?,
                 parent_id: r.get(2)?,
+                favorite: r.get(3)?,
             })
         })
         .map_err(|e| e.to_string())?;
@@ -104,13 +114,15 @@ pub fn list_hosts_by_group(
 
     let (sql, params): (&str, Vec<i64>) = match group_id {
         Some(id) => (
-            "SELECT id, name, host, port, username, auth_type, group_id
-             FROM hosts WHERE group_id = ?",
+            "SELECT id, name, host, port, username, auth_type, group_id, favorite
+             FROM hosts WHERE group_id = ? AND deleted_at IS NULL
+             ORDER BY sort_order, name COLLATE NOCASE, id",
             vec![id],
         ),
         None => (
-            "SELECT id, name, host, port, username, auth_type, group_id
-             FROM hosts WHERE group_id IS NULL",
+            "SELECT id, name, host, port, username, auth_type, group_id, favorite
+             FROM hosts WHERE group_id IS NULL AND deleted_at IS NULL
+             ORDER BY sort_order, name COLLATE NOCASE, id",
             vec![],
         ),
     };
@@ -127,6 +139,7 @@ pub fn list_hosts_by_group(
                 username: r.get(4)?,
                 auth_type: r.get(5)?,
                 group_id: r.get(6)?,
+                favorite: r.get(7)?,
             })
         })
         .map_err(|e| e.to_string())?;
@@ -139,14 +152,17 @@ pub fn create_group(
     name: String,
     parent_id: Option<i64>,
     db: tauri::State<Db>,
-) -> Result<(), String> {
-    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+) -> Result<(), CrudError> {
+    let conn = db.conn.lock().map_err(|_| CrudError::internal("DB lock failed"))?;
+    let name = validate_name("name", &name)?;
+    if let Some(parent_id) = parent_id {
+        ensure_group(&conn, parent_id, "parent_id")?;
+    }
 
     conn.execute(
         "INSERT INTO groups (name, parent_id) VALUES (?, ?)",
         rusqlite::params![name, parent_id],
-    )
-    .map_err(|e| e.to_string())?;
+    )?;
 
     Ok(())
 }
@@ -156,50 +172,64 @@ pub fn update_group(
     name: String,
     parent_id: Option<i64>,
     db: tauri::State<Db>,
-) -> Result<(), String> {
-    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+) -> Result<(), CrudError> {
+    let conn = db.conn.lock().map_err(|_| CrudError::internal("DB lock failed"))?;
+    update_group_row(&conn, id, &name, parent_id)
+}
+
+pub fn update_group_row(
+    conn: &rusqlite::Connection,
+    id: i64,
+    name: &str,
+    parent_id: Option<i64>,
+) -> Result<(), CrudError> {
+    ensure_group(conn, id, "id")?;
+    let name = validate_name("name", name)?;
+    ensure_valid_parent(conn, id, parent_id)?;
 
     conn.execute(
         "UPDATE groups SET name = ?, parent_id = ? WHERE id = ?",
         rusqlite::params![name, parent_id, id],
-    )
-    .map_err(|e| e.to_string())?;
+    )?;
 
     Ok(())
 }
 
+/// Only empty groups; `delete_group_recursive` trashes a whole subtree
 #[tauri::command]
-pub fn delete_group(id: i64, db: tauri::State<Db>) -> Result<(), String> {
-    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+pub fn delete_group(id: i64, db: tauri::State<Db>) -> Result<(), CrudError> {
+    let conn = db.conn.lock().map_err(|_| CrudError::internal("DB lock failed"))?;
+    ensure_group(&conn, id, "id")?;
 
-    // cek sub group
-    let sub_count: i64 = conn
-        .query_row(
-            "SELECT COUNT(*) FROM groups WHERE parent_id = ?",
-            rusqlite::params![id],
-            |r| r.get(0),
-        )
-        .map_err(|e| e.to_string())?;
+    let sub_count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM groups WHERE parent_id = ? AND deleted_at IS NULL",
+        rusqlite::params![id],
+        |r| r.get(0),
+    )?;
 
     if sub_count > 0 {
-        return Err("Group masih memiliki sub group".into());
+        return Err(CrudError::field(
+            CrudErrorCode::GroupHasSubgroups,
+            "id",
+            "Group still has sub groups",
+        ));
     }
 
-    // cek host
-    let host_count: i64 = conn
-        .query_row(
-            "SELECT COUNT(*) FROM hosts WHERE group_id = ?",
-            rusqlite::params![id],
-            |r| r.get(0),
-        )
-        .map_err(|e| e.to_string())?;
+    let host_count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM hosts WHERE group_id = ? AND deleted_at IS NULL",
+        rusqlite::params![id],
+        |r| r.get(0),
+    )?;
 
     if host_count > 0 {
-        return Err("Group masih memiliki host".into());
+        return Err(CrudError::field(
+            CrudErrorCode::GroupHasHosts,
+            "id",
+            "Group still has hosts",
+        ));
     }
 
-    conn.execute("DELETE FROM groups WHERE id = ?", rusqlite::params![id])
-        .map_err(|e| e.to_string())?;
+    trash_group(&conn, id).map_err(CrudError::internal)?;
 
     Ok(())
 }
@@ -214,24 +244,40 @@ pub fn create_host(
     auth_type: String,
     group_id: Option<i64>,
     db: tauri::State<Db>,
-) -> Result<(), String> {
-    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+) -> Result<(), CrudError> {
+    let conn = db.conn.lock().map_err(|_| CrudError::internal("DB lock failed"))?;
+    let input = HostInput {
+        name,
+        host,
+        port,
+        username,
+        auth_type,
+        group_id,
+    }
+    .validate(&conn)?;
 
     conn.execute(
         "INSERT INTO hosts (name, host, port, username, password, auth_type, group_id)
          VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-        rusqlite::params![name, host, port, username, password, auth_type, group_id],
-    )
-    .map_err(|e| e.to_string())?;
+        rusqlite::params![
+            input.name,
+            input.host,
+            input.port,
+            input.username,
+            password,
+            input.auth_type,
+            input.group_id
+        ],
+    )?;
 
     Ok(())
 }
 
 #[tauri::command]
-pub fn delete_host(id: i64, db: tauri::State<Db>) -> Result<(), String> {
-    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
-    conn.execute("DELETE FROM hosts WHERE id = ?", [id])
-        .map_err(|e| e.to_string())?;
+pub fn delete_host(id: i64, db: tauri::State<Db>) -> Result<(), CrudError> {
+    let conn = db.conn.lock().map_err(|_| CrudError::internal("DB lock failed"))?;
+    ensure_host(&conn, id)?;
+    trash_host(&conn, id).map_err(CrudError::internal)?;
     Ok(())
 }
 
@@ -269,29 +315,48 @@ pub fn update_host(
     auth_type: String,
     group_id: Option<i64>,
     db: tauri::State<Db>,
-) -> Result<(), String> {
-    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+) -> Result<(), CrudError> {
+    let conn = db.conn.lock().map_err(|_| CrudError::internal("DB lock failed"))?;
+    ensure_host(&conn, id)?;
+    let input = HostInput {
+        name,
+        host,
+        port,
+        username,
+        auth_type,
+        group_id,
+    }
+    .validate(&conn)?;
 
     conn.execute(
         "UPDATE hosts
          SET name = ?, host = ?, port = ?, username = ?, password = ?, auth_type = ?, group_id = ?
          WHERE id = ?",
-        rusqlite::params![name, host, port, username, password, auth_type, group_id, id],
-    )
-    .map_err(|e| e.to_string())?;
+        rusqlite::params![
+            input.name,
+            input.host,
+            input.port,
+            input.username,
+            password,
+            input.auth_type,
+            input.group_id,
+            id
+        ],
+    )?;
 
     Ok(())
 }
 
 #[tauri::command]
-pub fn rename_group(id: i64, name: String, db: tauri::State<Db>) -> Result<(), String> {
-    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+pub fn rename_group(id: i64, name: String, db: tauri::State<Db>) -> Result<(), CrudError> {
+    let conn = db.conn.lock().map_err(|_| CrudError::internal("DB lock failed"))?;
+    ensure_group(&conn, id, "id")?;
+    let name = validate_name("name", &name)?;
 
     conn.execute(
         "UPDATE groups SET name = ? WHERE id = ?",
         rusqlite::params![name, id],
-    )
-    .map_err(|e| e.to_string())?;
+    )?;
 
     Ok(())
 }
@@ -326,7 +391,7 @@ pub fn ssh_exec(
     let mut stmt = conn
         .prepare(
             "SELECT host, port, username, password
-         FROM hosts WHERE id = ?",
+         FROM hosts WHERE id = ? AND deleted_at IS NULL",
         )
         .map_err(|e| e.to_string())?;
 
@@ -4,6 +4,8 @@ use std::fs;
 use std::path::PathBuf;
 use tauri::{AppHandle, Manager};
 
+use crate::migrations::run_migrations;
+
 
 pub struct Db {
     pub conn: Mutex<Connection>,
@@ -19,15 +21,17 @@ pub fn init_db(app: &AppHandle) -> Db {
     fs::create_dir_all(&app_dir)
         .expect("failed to create app data dir");
 
-    let mut db_path = PathBuf::from(app_dir);
+    let mut db_path = PathBuf::from(&app_dir);
     db_path.push("nethopper.db");
 
-    let conn = Connection::open(&db_path)
+    let mut conn = Connection::open(&db_path)
         .expect("Failed to open database");
 
-    conn.execute_batch(
-        include_str!("../schema.sql")
-    ).expect("Failed to initialize schema");
+    conn.execute_batch("PRAGMA foreign_keys = ON;")
+        .expect("Failed to enable foreign keys");
+
+    run_migrations(&mut conn, &app_dir.join("backups"))
+        .expect("Failed to migrate database");
 
     Db {
         conn: Mutex::new(conn),
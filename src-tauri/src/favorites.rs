@@ -0,0 +1,104 @@
+use serde::Serialize;
+
+use crate::commands::{Group, Host};
+use crate::db::Db;
+
+/* =========================
+   MODELS
+========================= */
+
+#[derive(Serialize)]
+pub struct Favorites {
+    pub groups: Vec<Group>,
+    pub hosts: Vec<Host>,
+}
+
+/* =========================
+   TAURI COMMANDS
+========================= */
+
+#[tauri::command]
+pub fn set_group_favorite(id: i64, favorite: bool, db: tauri::State<Db>) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    let updated = conn
+        .execute(
+            "UPDATE groups SET favorite = ? WHERE id = ? AND deleted_at IS NULL",
+            rusqlite::params![favorite, id],
+        )
+        .map_err(|e| e.to_string())?;
+
+    if updated == 0 {
+        return Err("Group not found".into());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_host_favorite(id: i64, favorite: bool, db: tauri::State<Db>) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    let updated = conn
+        .execute(
+            "UPDATE hosts SET favorite = ? WHERE id = ? AND deleted_at IS NULL",
+            rusqlite::params![favorite, id],
+        )
+        .map_err(|e| e.to_string())?;
+
+    if updated == 0 {
+        return Err("Host not found".into());
+    }
+    Ok(())
+}
+
+/// Pinned groups and hosts from anywhere in the tree, by name
+#[tauri::command]
+pub fn list_favorites(db: tauri::State<Db>) -> Result<Favorites, String> {
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+
+    let groups = {
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, name, parent_id, favorite FROM groups
+                 WHERE favorite = 1 AND deleted_at IS NULL
+                 ORDER BY name COLLATE NOCASE, id",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([], |r| {
+                Ok(Group {
+                    id: r.get(0)?,
+                    name: r.get(1)?,
+                    parent_id: r.get(2)?,
+                    favorite: r.get(3)?,
+                })
+            })
+            .map_err(|e| e.to_string())?;
+        rows.filter_map(Result::ok).collect()
+    };
+
+    let hosts = {
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, name, host, port, username, auth_type, group_id, favorite FROM hosts
+                 WHERE favorite = 1 AND deleted_at IS NULL
+                 ORDER BY name COLLATE NOCASE, id",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([], |r| {
+                Ok(Host {
+                    id: r.get(0)?,
+                    name: r.get(1)?,
+                    host: r.get(2)?,
+                    port: r.get(3)?,
+                    username: r.get(4)?,
+                    auth_type: r.get(5)?,
+                    group_id: r.get(6)?,
+                    favorite: r.get(7)?,
+                })
+            })
+            .map_err(|e| e.to_string())?;
+        rows.filter_map(Result::ok).collect()
+    };
+
+    Ok(Favorites { groups, hosts })
+}
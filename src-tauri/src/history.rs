@@ -0,0 +1,246 @@
+use serde::Serialize;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::db::Db;
+
+/* =========================
+   CONFIG
+========================= */
+
+const DEFAULT_RECENT_LIMIT: i64 = 10;
+const DEFAULT_HISTORY_LIMIT: i64 = 100;
+const MAX_LIMIT: i64 = 1000;
+/// Oldest rows beyond this are dropped when a new session starts
+const MAX_HISTORY_ROWS: i64 = 10_000;
+
+/* =========================
+   MODELS
+========================= */
+
+/// Stage a session failed in, stored as `failure_kind`
+#[derive(Clone, Copy)]
+pub enum FailureKind {
+    Resolve,
+    Connect,
+    Handshake,
+    Auth,
+    Channel,
+    OutputLimit,
+    Io,
+}
+
+impl FailureKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            FailureKind::Resolve => "resolve",
+            FailureKind::Connect => "connect",
+            FailureKind::Handshake => "handshake",
+            FailureKind::Auth => "auth",
+            FailureKind::Channel => "channel",
+            FailureKind::OutputLimit => "output_limit",
+            FailureKind::Io => "io",
+        }
+    }
+}
+
+/// How a session ended, as reported by the SSH worker
+pub enum SessionOutcome {
+    Closed { exit_code: i32 },
+    Cancelled { exit_code: i32 },
+    Failed { kind: FailureKind, error: String },
+}
+
+#[derive(Serialize)]
+pub struct ConnectionRecord {
+    pub id: i64,
+    pub host_id: i64,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+    pub duration_ms: Option<i64>,
+    /// `running`, `closed`, `cancelled`, `failed`, or `interrupted` when the
+    /// app exited during the session
+    pub status: String,
+    pub exit_code: Option<i32>,
+    pub failure_kind: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct RecentHost {
+    pub id: i64,
+    pub name: String,
+    pub host: String,
+    pub port: i64,
+    pub username: String,
+    pub group_id: Option<i64>,
+    pub last_connected_at: String,
+    pub last_status: String,
+    pub connection_count: i64,
+}
+
+/* =========================
+   TAURI COMMANDS
+========================= */
+
+/// Hosts by their latest session, most recent first
+#[tauri::command]
+pub fn list_recent_hosts(limit: Option<i64>, db: tauri::State<Db>) -> Result<Vec<RecentHost>, String> {
+    let limit = limit.unwrap_or(DEFAULT_RECENT_LIMIT).clamp(1, MAX_LIMIT);
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+
+    let mut stmt = conn
+        .prepare(
+            "WITH latest AS (
+                 SELECT host_id, MAX(id) AS last_id, COUNT(*) AS n
+                 FROM connection_history GROUP BY host_id
+             )
+             SELECT h.id, h.name, h.host, h.port, h.username, h.group_id,
+                    c.started_at, c.status, l.n
+             FROM latest l
+             JOIN connection_history c ON c.id = l.last_id
+             JOIN hosts h ON h.id = l.host_id AND h.deleted_at IS NULL
+             ORDER BY l.last_id DESC
+             LIMIT ?",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map([limit], |r| {
+            Ok(RecentHost {
+                id: r.get(0)?,
+                name: r.get(1)?,
+                host: r.get(2)?,
+                port: r.get(3)?,
+                username: r.get(4)?,
+                group_id: r.get(5)?,
+                last_connected_at: r.get(6)?,
+                last_status: r.get(7)?,
+                connection_count: r.get(8)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.filter_map(Result::ok).collect())
+}
+
+/// Sessions of one host, newest first
+#[tauri::command]
+pub fn get_host_history(
+    host_id: i64,
+    limit: Option<i64>,
+    db: tauri::State<Db>,
+) -> Result<Vec<ConnectionRecord>, String> {
+    let limit = limit.unwrap_or(DEFAULT_HISTORY_LIMIT).clamp(1, MAX_LIMIT);
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, host_id, started_at, ended_at, duration_ms, status, exit_code, failure_kind, error
+             FROM connection_history WHERE host_id = ?
+             ORDER BY id DESC LIMIT ?",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map(rusqlite::params![host_id, limit], |r| {
+            Ok(ConnectionRecord {
+                id: r.get(0)?,
+                host_id: r.get(1)?,
+                started_at: r.get(2)?,
+                ended_at: r.get(3)?,
+                duration_ms: r.get(4)?,
+                status: r.get(5)?,
+                exit_code: r.get(6)?,
+                failure_kind: r.get(7)?,
+                error: r.get(8)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.filter_map(Result::ok).collect())
+}
+
+/// `host_id = None` clears the history of every host
+#[tauri::command]
+pub fn clear_connection_history(host_id: Option<i64>, db: tauri::State<Db>) -> Result<usize, String> {
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    conn.execute(
+        "DELETE FROM connection_history WHERE host_id = IFNULL(?1, host_id)",
+        [host_id],
+    )
+    .map_err(|e| e.to_string())
+}
+
+/* =========================
+   RECORDING
+========================= */
+
+/// Returns the history row id. History must never block a session, so
+/// failures are only logged.
+pub fn record_session_start(app: &AppHandle, host_id: i64) -> Option<i64> {
+    match insert_session(app, host_id) {
+        Ok(id) => Some(id),
+        Err(e) => {
+            println!("[History] Cannot record session start: {}", e);
+            None
+        }
+    }
+}
+
+pub fn record_session_end(app: &AppHandle, id: Option<i64>, duration: Duration, outcome: &SessionOutcome) {
+    let Some(id) = id else {
+        return;
+    };
+    if let Err(e) = finish_session(app, id, duration, outcome) {
+        println!("[History] Cannot record session end: {}", e);
+    }
+}
+
+fn insert_session(app: &AppHandle, host_id: i64) -> Result<i64, String> {
+    let db = app.state::<Db>();
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+
+    conn.execute("INSERT INTO connection_history (host_id) VALUES (?)", [host_id])
+        .map_err(|e| e.to_string())?;
+    let id = conn.last_insert_rowid();
+
+    conn.execute("DELETE FROM connection_history WHERE id <= ?", [id - MAX_HISTORY_ROWS])
+        .map_err(|e| e.to_string())?;
+    Ok(id)
+}
+
+fn finish_session(app: &AppHandle, id: i64, duration: Duration, outcome: &SessionOutcome) -> Result<(), String> {
+    let (status, exit_code, failure_kind, error) = match outcome {
+        SessionOutcome::Closed { exit_code } => ("closed", Some(*exit_code), None, None),
+        SessionOutcome::Cancelled { exit_code } => ("cancelled", Some(*exit_code), None, None),
+        SessionOutcome::Failed { kind, error } => ("failed", None, Some(kind.as_str()), Some(error.as_str())),
+    };
+
+    let db = app.state::<Db>();
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    conn.execute(
+        "UPDATE connection_history
+         SET ended_at = CURRENT_TIMESTAMP, duration_ms = ?, status = ?, exit_code = ?,
+             failure_kind = ?, error = ?
+         WHERE id = ?",
+        rusqlite::params![duration.as_millis() as i64, status, exit_code, failure_kind, error, id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Sessions still marked running were cut off by the previous app exit;
+/// called once from setup
+pub fn close_interrupted_sessions(app: &AppHandle) {
+    let db = app.state::<Db>();
+    let Ok(conn) = db.conn.lock() else {
+        return;
+    };
+    if let Err(e) = conn.execute(
+        "UPDATE connection_history SET status = 'interrupted' WHERE status = 'running'",
+        [],
+    ) {
+        println!("[History] Cannot close interrupted sessions: {}", e);
+    }
+}
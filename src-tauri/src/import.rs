@@ -0,0 +1,386 @@
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+
+use crate::validation::{CrudError, CrudErrorCode, HostInput};
+
+/* =========================
+   SHARED IMPORT PIPELINE
+   Importers parse their source into `ImportHost`s, then preview/apply them here
+========================= */
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ImportHost {
+    pub name: String,
+    pub host: String,
+    pub port: i64,
+    pub username: String,
+    pub password: Option<String>,
+    pub auth_type: String,
+    pub identity_file: Option<String>,
+    pub proxy_jump: Option<String>,
+    /// Group names below the import target, outermost first
+    pub group_path: Vec<String>,
+}
+
+/// What to do with a host that already exists in the inventory
+#[derive(Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateMode {
+    #[default]
+    Skip,
+    Update,
+    CreateAnyway,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportItemStatus {
+    New,
+    Duplicate,
+}
+
+#[derive(Serialize)]
+pub struct ImportPreviewItem {
+    #[serde(flatten)]
+    pub host: ImportHost,
+    pub status: ImportItemStatus,
+    pub existing_id: Option<i64>,
+}
+
+#[derive(Serialize)]
+pub struct ImportPreview {
+    pub items: Vec<ImportPreviewItem>,
+    /// Group paths (joined with " / ") that would be created
+    pub new_groups: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Serialize, Default)]
+pub struct ImportResult {
+    pub created_hosts: usize,
+    pub updated_hosts: usize,
+    pub skipped_hosts: usize,
+    pub created_groups: usize,
+    pub warnings: Vec<String>,
+    /// Ids of every imported host, including skipped duplicates
+    #[serde(skip)]
+    pub host_ids: Vec<i64>,
+}
+
+/* =========================
+   PREVIEW
+========================= */
+
+pub fn preview_import(
+    conn: &Connection,
+    hosts: Vec<ImportHost>,
+    root_group_id: Option<i64>,
+    warnings: Vec<String>,
+) -> Result<ImportPreview, String> {
+    let mut new_groups = BTreeSet::new();
+    let mut items = Vec::with_capacity(hosts.len());
+
+    for host in hosts {
+        let group_id = resolve_group_path(conn, root_group_id, &host.group_path, &mut new_groups)?;
+        let existing_id = find_duplicate(conn, &host, group_id, None)?;
+
+        items.push(ImportPreviewItem {
+            status: if existing_id.is_some() {
+                ImportItemStatus::Duplicate
+            } else {
+                ImportItemStatus::New
+            },
+            existing_id,
+            host,
+        });
+    }
+
+    Ok(ImportPreview {
+        items,
+        new_groups: new_groups.into_iter().collect(),
+        warnings,
+    })
+}
+
+/// Walks `path` below `root` without creating anything. Missing segments are
+/// recorded in `missing`; returns the deepest existing group only if the whole path exists.
+fn resolve_group_path(
+    conn: &Connection,
+    root: Option<i64>,
+    path: &[String],
+    missing: &mut BTreeSet<String>,
+) -> Result<Option<Option<i64>>, String> {
+    let mut parent = root;
+
+    for (i, name) in path.iter().enumerate() {
+        match find_group(conn, parent, name)? {
+            Some(id) => parent = Some(id),
+            None => {
+                for depth in i..path.len() {
+                    missing.insert(path[..=depth].join(" / "));
+                }
+                return Ok(None);
+            }
+        }
+    }
+
+    Ok(Some(parent))
+}
+
+/* =========================
+   APPLY
+========================= */
+
+pub fn apply_import(
+    conn: &mut Connection,
+    hosts: Vec<ImportHost>,
+    root_group_id: Option<i64>,
+    duplicates: DuplicateMode,
+    warnings: Vec<String>,
+) -> Result<ImportResult, String> {
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let mut result = ImportResult {
+        warnings,
+        ..Default::default()
+    };
+    import_hosts(&tx, hosts, root_group_id, duplicates, None, &mut result)?;
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(result)
+}
+
+/// `apply_import` inside the caller's transaction. With `scope`, a host only
+/// counts as a duplicate by address when its id is in the scope.
+pub fn import_hosts(
+    conn: &Connection,
+    hosts: Vec<ImportHost>,
+    root_group_id: Option<i64>,
+    duplicates: DuplicateMode,
+    scope: Option<&HashSet<i64>>,
+    result: &mut ImportResult,
+) -> Result<(), String> {
+    for host in hosts {
+        let group_id = ensure_group_path(conn, root_group_id, &host.group_path, result)?;
+        let existing = find_duplicate(conn, &host, Some(group_id), scope)?;
+
+        match (existing, duplicates) {
+            (Some(id), DuplicateMode::Skip) => {
+                result.skipped_hosts += 1;
+                result.host_ids.push(id);
+            }
+            (Some(id), DuplicateMode::Update) => {
+                if skip_invalid(update_host_row(conn, id, &host, group_id), &host, &mut result.warnings)?.is_some() {
+                    result.updated_hosts += 1;
+                    result.host_ids.push(id);
+                }
+            }
+            _ => {
+                if let Some(id) = skip_invalid(insert_host_row(conn, &host, group_id), &host, &mut result.warnings)? {
+                    result.created_hosts += 1;
+                    result.host_ids.push(id);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn ensure_group_path(
+    conn: &Connection,
+    root: Option<i64>,
+    path: &[String],
+    result: &mut ImportResult,
+) -> Result<Option<i64>, String> {
+    let mut parent = root;
+
+    for name in path {
+        parent = Some(match find_group(conn, parent, name)? {
+            Some(id) => id,
+            None => {
+                conn.execute(
+                    "INSERT INTO groups (name, parent_id) VALUES (?, ?)",
+                    rusqlite::params![name, parent],
+                )
+                .map_err(|e| e.to_string())?;
+                result.created_groups += 1;
+                conn.last_insert_rowid()
+            }
+        });
+    }
+
+    Ok(parent)
+}
+
+pub fn find_group(conn: &Connection, parent_id: Option<i64>, name: &str) -> Result<Option<i64>, String> {
+    conn.query_row(
+        "SELECT id FROM groups WHERE name = ? AND parent_id IS ? AND deleted_at IS NULL ORDER BY id LIMIT 1",
+        rusqlite::params![name, parent_id],
+        |r| r.get(0),
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+/// A host is a duplicate when the same name already sits in the target group, or
+/// the same user@host:port exists anywhere in the inventory (or in `scope`).
+/// `group_id` is `None` when the target group does not exist yet.
+fn find_duplicate(
+    conn: &Connection,
+    host: &ImportHost,
+    group_id: Option<Option<i64>>,
+    scope: Option<&HashSet<i64>>,
+) -> Result<Option<i64>, String> {
+    if let Some(group_id) = group_id {
+        let by_name: Option<i64> = conn
+            .query_row(
+                "SELECT id FROM hosts WHERE name = ? AND group_id IS ? AND deleted_at IS NULL ORDER BY id LIMIT 1",
+                rusqlite::params![host.name, group_id],
+                |r| r.get(0),
+            )
+            .optional()
+            .map_err(|e| e.to_string())?;
+        if by_name.is_some() {
+            return Ok(by_name);
+        }
+    }
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id FROM hosts WHERE host = ? AND port = ? AND username = ? AND deleted_at IS NULL
+             ORDER BY id",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(rusqlite::params![host.host, host.port, host.username], |r| r.get(0))
+        .map_err(|e| e.to_string())?;
+
+    let found = rows
+        .filter_map(Result::ok)
+        .find(|id| scope.is_none_or(|s| s.contains(id)));
+    Ok(found)
+}
+
+pub fn insert_host_row(conn: &Connection, host: &ImportHost, group_id: Option<i64>) -> Result<i64, CrudError> {
+    let input = validate_host(conn, host, group_id)?;
+    conn.execute(
+        "INSERT INTO hosts (name, host, port, username, password, auth_type, identity_file, proxy_jump, group_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        rusqlite::params![
+            input.name,
+            input.host,
+            input.port,
+            input.username,
+            host.password,
+            input.auth_type,
+            host.identity_file,
+            host.proxy_jump,
+            group_id
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Keeps the stored password when the import carries none
+pub fn update_host_row(
+    conn: &Connection,
+    id: i64,
+    host: &ImportHost,
+    group_id: Option<i64>,
+) -> Result<(), CrudError> {
+    let input = validate_host(conn, host, group_id)?;
+    conn.execute(
+        "UPDATE hosts
+         SET name = ?1, host = ?2, port = ?3, username = ?4, password = COALESCE(?5, password),
+             auth_type = ?6, identity_file = ?7, proxy_jump = ?8, group_id = ?9
+         WHERE id = ?10",
+        rusqlite::params![
+            input.name,
+            input.host,
+            input.port,
+            input.username,
+            host.password,
+            input.auth_type,
+            host.identity_file,
+            host.proxy_jump,
+            group_id,
+            id
+        ],
+    )?;
+    Ok(())
+}
+
+fn validate_host(conn: &Connection, host: &ImportHost, group_id: Option<i64>) -> Result<HostInput, CrudError> {
+    HostInput {
+        name: host.name.clone(),
+        host: host.host.clone(),
+        port: host.port,
+        username: host.username.clone(),
+        auth_type: host.auth_type.clone(),
+        group_id,
+    }
+    .validate_imported(conn)
+}
+
+/// A host the validator rejects is left out with a warning instead of
+/// failing the whole import; database errors still fail it
+pub fn skip_invalid<T>(
+    written: Result<T, CrudError>,
+    host: &ImportHost,
+    warnings: &mut Vec<String>,
+) -> Result<Option<T>, String> {
+    match written {
+        Ok(value) => Ok(Some(value)),
+        Err(e) if e.code == CrudErrorCode::Internal => Err(e.into()),
+        Err(e) => {
+            warnings.push(format!("{}: not imported: {}", host.name, e));
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn host(name: &str, address: &str, port: i64) -> ImportHost {
+        ImportHost {
+            name: name.into(),
+            host: address.into(),
+            port,
+            username: String::new(),
+            password: None,
+            auth_type: "password".into(),
+            identity_file: None,
+            proxy_jump: None,
+            group_path: vec!["lab".into()],
+        }
+    }
+
+    #[test]
+    fn invalid_hosts_become_warnings() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        crate::migrations::run_migrations(&mut conn, Path::new("/tmp")).unwrap();
+
+        let hosts = vec![
+            host(" web ", "web.lan.", 22),
+            host("bad-port", "db.lan", 70000),
+            host("bad-host", "not a host", 22),
+            host("", "cache.lan", 22),
+        ];
+        let result = apply_import(&mut conn, hosts, None, DuplicateMode::Skip, Vec::new()).unwrap();
+
+        assert_eq!(result.created_hosts, 1);
+        assert_eq!(result.created_groups, 1);
+        assert_eq!(result.warnings.len(), 3);
+        assert!(result.warnings[0].starts_with("bad-port: not imported: Port"));
+        assert!(result.warnings[1].starts_with("bad-host: not imported:"));
+        assert!(result.warnings[2].starts_with(": not imported: name is required"));
+
+        let (name, username): (String, String) = conn
+            .query_row("SELECT name, username FROM hosts", [], |r| Ok((r.get(0)?, r.get(1)?)))
+            .unwrap();
+        assert_eq!(name, "web");
+        assert_eq!(username, "");
+    }
+}
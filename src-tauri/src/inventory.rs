@@ -0,0 +1,683 @@
+use aes_gcm::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng},
+    Aes256Gcm, Nonce,
+};
+use base64::{engine::general_purpose::STANDARD as B64, Engine};
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fs, path::Path};
+use tauri::{AppHandle, Manager};
+
+use crate::backup::take_snapshot;
+use crate::db::Db;
+use crate::import::{ensure_group_path, insert_host_row, skip_invalid, update_host_row, ImportHost, ImportResult};
+use crate::notes::{load_all_fields, replace_fields, CustomField, Owner};
+use crate::tags::{add_host_tags, load_all_host_tags};
+
+/* =========================
+   CONFIG
+========================= */
+
+const FORMAT_NAME: &str = "nethopper-inventory";
+const FORMAT_VERSION: u32 = 1;
+const KDF_ITERATIONS: u32 = 210_000;
+/// Bounds for the count read from an imported file: below it the key
+/// derivation is ineffective, above it the import hangs
+const KDF_ITERATIONS_RANGE: std::ops::RangeInclusive<u32> = 10_000..=10_000_000;
+
+/* =========================
+   DOCUMENT
+========================= */
+
+#[derive(Serialize, Deserialize)]
+pub struct InventoryDocument {
+    pub format: String,
+    pub version: u32,
+    #[serde(default)]
+    pub exported_at: String,
+    /// Present when host passwords are included, encrypted with a passphrase
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secrets: Option<SecretsInfo>,
+    #[serde(default)]
+    pub groups: Vec<InventoryGroup>,
+    /// Hosts without a group
+    #[serde(default)]
+    pub hosts: Vec<InventoryHost>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SecretsInfo {
+    pub cipher: String,
+    pub kdf: String,
+    pub iterations: u32,
+    pub salt: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct InventoryGroup {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub favorite: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<CustomField>,
+    #[serde(default)]
+    pub groups: Vec<InventoryGroup>,
+    #[serde(default)]
+    pub hosts: Vec<InventoryHost>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct InventoryHost {
+    pub name: String,
+    pub host: String,
+    #[serde(default = "default_port")]
+    pub port: i64,
+    pub username: String,
+    #[serde(default = "default_auth_type")]
+    pub auth_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identity_file: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy_jump: Option<String>,
+    /// base64(nonce || ciphertext), only with `secrets`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub favorite: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<CustomField>,
+}
+
+fn default_port() -> i64 {
+    22
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+fn default_auth_type() -> String {
+    "password".into()
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum InventoryFormat {
+    Json,
+    Yaml,
+}
+
+impl InventoryFormat {
+    /// `.yaml`/`.yml` means YAML, anything else JSON
+    fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("yaml") || ext.eq_ignore_ascii_case("yml") => {
+                InventoryFormat::Yaml
+            }
+            _ => InventoryFormat::Json,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum InventoryImportMode {
+    /// Delete every group and host first. Connection history, tunnel profiles
+    /// and import sources are not part of the document and are lost; the
+    /// result warns about each.
+    Replace,
+    /// Match groups by name path and hosts by name inside their group, update
+    /// matches. Tags are added, notes and fields only replaced when the
+    /// document has them, favorites only ever set.
+    Merge,
+    /// Like merge, but existing hosts are left untouched
+    SkipExisting,
+}
+
+#[derive(Serialize)]
+pub struct InventoryExportResult {
+    pub groups: usize,
+    pub hosts: usize,
+    pub secrets: bool,
+}
+
+/* =========================
+   TAURI COMMANDS
+========================= */
+
+/// Passwords are only written when `passphrase` is given
+#[tauri::command]
+pub fn export_inventory(
+    path: String,
+    format: Option<InventoryFormat>,
+    passphrase: Option<String>,
+    db: tauri::State<Db>,
+) -> Result<InventoryExportResult, String> {
+    let path = Path::new(&path);
+    let format = format.unwrap_or_else(|| InventoryFormat::from_path(path));
+
+    let cipher = passphrase
+        .filter(|p| !p.is_empty())
+        .map(|p| SecretCipher::create(&p));
+
+    let doc = {
+        let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+        build_document(&conn, cipher.as_ref())?
+    };
+
+    let text = match format {
+        InventoryFormat::Json => serde_json::to_string_pretty(&doc).map_err(|e| e.to_string())?,
+        InventoryFormat::Yaml => serde_yaml::to_string(&doc).map_err(|e| e.to_string())?,
+    };
+    fs::write(path, text).map_err(|e| e.to_string())?;
+
+    let (groups, hosts) = count_tree(&doc.groups);
+    Ok(InventoryExportResult {
+        groups,
+        hosts: hosts + doc.hosts.len(),
+        secrets: doc.secrets.is_some(),
+    })
+}
+
+#[tauri::command]
+pub fn import_inventory(
+    path: String,
+    format: Option<InventoryFormat>,
+    mode: InventoryImportMode,
+    passphrase: Option<String>,
+    app: AppHandle,
+) -> Result<ImportResult, String> {
+    let path = Path::new(&path);
+    let format = format.unwrap_or_else(|| InventoryFormat::from_path(path));
+
+    let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let doc: InventoryDocument = match format {
+        InventoryFormat::Json => serde_json::from_str(&text).map_err(|e| e.to_string())?,
+        InventoryFormat::Yaml => serde_yaml::from_str(&text).map_err(|e| e.to_string())?,
+    };
+
+    if doc.format != FORMAT_NAME {
+        return Err(format!("Not a NetHopper inventory (format '{}')", doc.format));
+    }
+    if doc.version > FORMAT_VERSION {
+        return Err(format!(
+            "Inventory version {} is newer than the supported version {}",
+            doc.version, FORMAT_VERSION
+        ));
+    }
+
+    let mut warnings = Vec::new();
+    let cipher = match (&doc.secrets, passphrase.filter(|p| !p.is_empty())) {
+        (Some(info), Some(passphrase)) => Some(SecretCipher::open(info, &passphrase)?),
+        (Some(_), None) => {
+            warnings.push("Inventory contains encrypted passwords, no passphrase given: passwords not imported".into());
+            None
+        }
+        (None, _) => None,
+    };
+
+    let entries = flatten_document(&doc, cipher.as_ref())?;
+
+    if mode == InventoryImportMode::Replace {
+        take_snapshot(&app, "pre-import")?;
+    }
+
+    let db = app.state::<Db>();
+    let mut conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    apply_inventory(&mut conn, entries, mode, warnings)
+}
+
+/* =========================
+   EXPORT
+========================= */
+
+fn build_document(conn: &Connection, cipher: Option<&SecretCipher>) -> Result<InventoryDocument, String> {
+    let mut group_fields = load_all_fields(conn, Owner::Group)?;
+    let mut host_fields = load_all_fields(conn, Owner::Host)?;
+    let mut host_tags = load_all_host_tags(conn)?;
+
+    let mut children: HashMap<Option<i64>, Vec<(i64, InventoryGroup)>> = HashMap::new();
+    {
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, name, parent_id, favorite, notes FROM groups WHERE deleted_at IS NULL
+                 ORDER BY sort_order, name, id",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([], |r| {
+                Ok((
+                    r.get::<_, i64>(0)?,
+                    r.get::<_, Option<i64>>(2)?,
+                    InventoryGroup {
+                        name: r.get(1)?,
+                        favorite: r.get(3)?,
+                        notes: r.get(4)?,
+                        fields: Vec::new(),
+                        groups: Vec::new(),
+                        hosts: Vec::new(),
+                    },
+                ))
+            })
+            .map_err(|e| e.to_string())?;
+        for (id, parent, mut group) in rows.filter_map(Result::ok) {
+            group.fields = group_fields.remove(&id).unwrap_or_default();
+            children.entry(parent).or_default().push((id, group));
+        }
+    }
+
+    let mut hosts: HashMap<Option<i64>, Vec<InventoryHost>> = HashMap::new();
+    {
+        let mut stmt = conn
+            .prepare(
+                "SELECT name, host, port, username, auth_type, identity_file, proxy_jump, password, group_id,
+                        id, favorite, notes
+                 FROM hosts WHERE deleted_at IS NULL ORDER BY sort_order, name, id",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([], |r| {
+                let password: Option<String> = r.get(7)?;
+                Ok((
+                    r.get::<_, Option<i64>>(8)?,
+                    r.get::<_, i64>(9)?,
+                    InventoryHost {
+                        name: r.get(0)?,
+                        host: r.get(1)?,
+                        port: r.get(2)?,
+                        username: r.get(3)?,
+                        auth_type: r.get(4)?,
+                        identity_file: r.get(5)?,
+                        proxy_jump: r.get(6)?,
+                        password,
+                        tags: Vec::new(),
+                        favorite: r.get(10)?,
+                        notes: r.get(11)?,
+                        fields: Vec::new(),
+                    },
+                ))
+            })
+            .map_err(|e| e.to_string())?;
+
+        for (group_id, id, mut host) in rows.filter_map(Result::ok) {
+            host.tags = host_tags.remove(&id).unwrap_or_default();
+            host.fields = host_fields.remove(&id).unwrap_or_default();
+            host.password = match (cipher, host.password.filter(|p| !p.is_empty())) {
+                (Some(cipher), Some(password)) => Some(cipher.encrypt(&password)?),
+                _ => None,
+            };
+            hosts.entry(group_id).or_default().push(host);
+        }
+    }
+
+    Ok(InventoryDocument {
+        format: FORMAT_NAME.into(),
+        version: FORMAT_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        secrets: cipher.map(SecretCipher::info),
+        groups: build_groups(None, &mut children, &mut hosts),
+        hosts: hosts.remove(&None).unwrap_or_default(),
+    })
+}
+
+fn build_groups(
+    parent: Option<i64>,
+    children: &mut HashMap<Option<i64>, Vec<(i64, InventoryGroup)>>,
+    hosts: &mut HashMap<Option<i64>, Vec<InventoryHost>>,
+) -> Vec<InventoryGroup> {
+    children
+        .remove(&parent)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(id, group)| InventoryGroup {
+            groups: build_groups(Some(id), children, hosts),
+            hosts: hosts.remove(&Some(id)).unwrap_or_default(),
+            ..group
+        })
+        .collect()
+}
+
+fn count_tree(groups: &[InventoryGroup]) -> (usize, usize) {
+    groups.iter().fold((0, 0), |(g, h), group| {
+        let (sub_g, sub_h) = count_tree(&group.groups);
+        (g + 1 + sub_g, h + group.hosts.len() + sub_h)
+    })
+}
+
+/* =========================
+   IMPORT
+========================= */
+
+/// Document entries in file order, every group before its children
+struct Flattened<'a> {
+    groups: Vec<(Vec<String>, &'a InventoryGroup)>,
+    hosts: Vec<(ImportHost, &'a InventoryHost)>,
+}
+
+fn flatten_document<'a>(
+    doc: &'a InventoryDocument,
+    cipher: Option<&SecretCipher>,
+) -> Result<Flattened<'a>, String> {
+    let mut out = Flattened {
+        groups: Vec::new(),
+        hosts: Vec::new(),
+    };
+    flatten_hosts(&doc.hosts, &[], cipher, &mut out)?;
+    flatten_groups(&doc.groups, &[], cipher, &mut out)?;
+    Ok(out)
+}
+
+fn flatten_groups<'a>(
+    groups: &'a [InventoryGroup],
+    path: &[String],
+    cipher: Option<&SecretCipher>,
+    out: &mut Flattened<'a>,
+) -> Result<(), String> {
+    for group in groups {
+        let mut path = path.to_vec();
+        path.push(group.name.clone());
+        // listed on its own so groups without hosts are created too
+        out.groups.push((path.clone(), group));
+        flatten_hosts(&group.hosts, &path, cipher, out)?;
+        flatten_groups(&group.groups, &path, cipher, out)?;
+    }
+    Ok(())
+}
+
+fn flatten_hosts<'a>(
+    hosts: &'a [InventoryHost],
+    path: &[String],
+    cipher: Option<&SecretCipher>,
+    out: &mut Flattened<'a>,
+) -> Result<(), String> {
+    for host in hosts {
+        let password = match (cipher, &host.password) {
+            (Some(cipher), Some(sealed)) => Some(cipher.decrypt(sealed)?),
+            _ => None,
+        };
+        out.hosts.push((
+            ImportHost {
+                name: host.name.clone(),
+                host: host.host.clone(),
+                port: host.port,
+                username: host.username.clone(),
+                password,
+                auth_type: host.auth_type.clone(),
+                identity_file: host.identity_file.clone(),
+                proxy_jump: host.proxy_jump.clone(),
+                group_path: path.to_vec(),
+            },
+            host,
+        ));
+    }
+    Ok(())
+}
+
+fn apply_inventory(
+    conn: &mut Connection,
+    entries: Flattened,
+    mode: InventoryImportMode,
+    warnings: Vec<String>,
+) -> Result<ImportResult, String> {
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let mut result = ImportResult {
+        warnings,
+        ..Default::default()
+    };
+
+    if mode == InventoryImportMode::Replace {
+        result.warnings.extend(replace_losses(&tx)?);
+        tx.execute_batch("DELETE FROM hosts; DELETE FROM groups; DELETE FROM trash;")
+            .map_err(|e| e.to_string())?;
+    }
+
+    for (path, group) in &entries.groups {
+        let created_before = result.created_groups;
+        let Some(group_id) = ensure_group_path(&tx, None, path, &mut result)? else {
+            continue;
+        };
+        // parents were handled first, so only the group itself can be new
+        let created = result.created_groups > created_before;
+        if created || mode != InventoryImportMode::SkipExisting {
+            apply_details(
+                &tx,
+                Owner::Group,
+                group_id,
+                (group.favorite, &group.notes, &group.fields),
+                &path.join(" / "),
+                &mut result.warnings,
+            )?;
+        }
+    }
+
+    for (host, details) in entries.hosts {
+        let group_id = ensure_group_path(&tx, None, &host.group_path, &mut result)?;
+        let existing: Option<i64> = tx
+            .query_row(
+                "SELECT id FROM hosts WHERE name = ? AND group_id IS ? AND deleted_at IS NULL ORDER BY id LIMIT 1",
+                rusqlite::params![host.name, group_id],
+                |r| r.get(0),
+            )
+            .optional()
+            .map_err(|e| e.to_string())?;
+
+        let id = match (existing, mode) {
+            (Some(_), InventoryImportMode::SkipExisting) => {
+                result.skipped_hosts += 1;
+                continue;
+            }
+            (Some(id), _) => {
+                if skip_invalid(update_host_row(&tx, id, &host, group_id), &host, &mut result.warnings)?.is_none() {
+                    continue;
+                }
+                result.updated_hosts += 1;
+                id
+            }
+            (None, _) => {
+                let Some(id) = skip_invalid(insert_host_row(&tx, &host, group_id), &host, &mut result.warnings)? else {
+                    continue;
+                };
+                result.created_hosts += 1;
+                id
+            }
+        };
+
+        apply_details(
+            &tx,
+            Owner::Host,
+            id,
+            (details.favorite, &details.notes, &details.fields),
+            &host.name,
+            &mut result.warnings,
+        )?;
+        if let Err(e) = add_host_tags(&tx, id, details.tags.clone()) {
+            result.warnings.push(format!("{}: tags not imported: {}", host.name, e));
+        }
+    }
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(result)
+}
+
+/// Data that Replace deletes and the document cannot bring back
+fn replace_losses(conn: &Connection) -> Result<Vec<String>, String> {
+    let checks = [
+        ("SELECT COUNT(*) FROM tunnel_profiles", "tunnel profile(s) removed with their hosts"),
+        ("SELECT COUNT(*) FROM import_sources", "import source(s) removed with their groups"),
+        ("SELECT COUNT(*) FROM connection_history", "connection history entries removed with their hosts"),
+        ("SELECT COUNT(*) FROM trash", "trash entries purged"),
+    ];
+
+    let mut warnings = Vec::new();
+    for (sql, what) in checks {
+        let count: i64 = conn.query_row(sql, [], |r| r.get(0)).map_err(|e| e.to_string())?;
+        if count > 0 {
+            warnings.push(format!("{} {}", count, what));
+        }
+    }
+    Ok(warnings)
+}
+
+/// Favorites are only ever set and notes and fields only replaced when the
+/// document has them, so merging an older export keeps what was added since.
+/// Invalid fields are reported in `warnings` instead of failing the import.
+fn apply_details(
+    conn: &Connection,
+    owner: Owner,
+    id: i64,
+    (favorite, notes, fields): (bool, &Option<String>, &[CustomField]),
+    label: &str,
+    warnings: &mut Vec<String>,
+) -> Result<(), String> {
+    conn.execute(
+        &format!(
+            "UPDATE {} SET favorite = MAX(favorite, ?), notes = COALESCE(?, notes) WHERE id = ?",
+            owner.table()
+        ),
+        rusqlite::params![favorite, notes.as_deref().filter(|n| !n.trim().is_empty()), id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    if !fields.is_empty() {
+        if let Err(e) = replace_fields(conn, owner, id, fields) {
+            warnings.push(format!("{}: fields not imported: {}", label, e));
+        }
+    }
+    Ok(())
+}
+
+/* =========================
+   SECRETS
+   AES-256-GCM, key from the passphrase with PBKDF2-HMAC-SHA256
+========================= */
+
+struct SecretCipher {
+    cipher: Aes256Gcm,
+    salt: [u8; 16],
+}
+
+impl SecretCipher {
+    fn create(passphrase: &str) -> Self {
+        let mut salt = [0u8; 16];
+        OsRng.fill_bytes(&mut salt);
+        Self::with_salt(passphrase, salt, KDF_ITERATIONS)
+    }
+
+    fn open(info: &SecretsInfo, passphrase: &str) -> Result<Self, String> {
+        if info.cipher != "aes-256-gcm" || info.kdf != "pbkdf2-sha256" {
+            return Err(format!("Unsupported secrets encryption {}/{}", info.cipher, info.kdf));
+        }
+        if !KDF_ITERATIONS_RANGE.contains(&info.iterations) {
+            return Err(format!(
+                "Unsupported key derivation iteration count {}, expected {} to {}",
+                info.iterations,
+                KDF_ITERATIONS_RANGE.start(),
+                KDF_ITERATIONS_RANGE.end()
+            ));
+        }
+        let salt: [u8; 16] = B64
+            .decode(&info.salt)
+            .ok()
+            .and_then(|s| s.try_into().ok())
+            .ok_or("Invalid secrets salt")?;
+        Ok(Self::with_salt(passphrase, salt, info.iterations))
+    }
+
+    fn with_salt(passphrase: &str, salt: [u8; 16], iterations: u32) -> Self {
+        let mut key = [0u8; 32];
+        pbkdf2::pbkdf2_hmac::<sha2::Sha256>(passphrase.as_bytes(), &salt, iterations, &mut key);
+        SecretCipher {
+            cipher: Aes256Gcm::new_from_slice(&key).expect("32 byte key"),
+            salt,
+        }
+    }
+
+    fn info(&self) -> SecretsInfo {
+        SecretsInfo {
+            cipher: "aes-256-gcm".into(),
+            kdf: "pbkdf2-sha256".into(),
+            iterations: KDF_ITERATIONS,
+            salt: B64.encode(self.salt),
+        }
+    }
+
+    fn encrypt(&self, plain: &str) -> Result<String, String> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let sealed = self
+            .cipher
+            .encrypt(&nonce, plain.as_bytes())
+            .map_err(|_| "Encryption failed")?;
+
+        let mut out = nonce.to_vec();
+        out.extend_from_slice(&sealed);
+        Ok(B64.encode(out))
+    }
+
+    fn decrypt(&self, sealed: &str) -> Result<String, String> {
+        let data = B64.decode(sealed).map_err(|_| "Invalid encrypted password")?;
+        if data.len() < 12 {
+            return Err("Invalid encrypted password".into());
+        }
+        let (nonce, body) = data.split_at(12);
+        let plain = self
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), body)
+            .map_err(|_| "Wrong passphrase or corrupted secrets")?;
+        String::from_utf8(plain).map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secrets(iterations: u32, salt: &str) -> SecretsInfo {
+        SecretsInfo {
+            cipher: "aes-256-gcm".into(),
+            kdf: "pbkdf2-sha256".into(),
+            iterations,
+            salt: salt.into(),
+        }
+    }
+
+    #[test]
+    fn secrets_round_trip() {
+        // the lowest accepted count keeps the test fast
+        let sealed_by = SecretCipher::with_salt("correct horse", [7u8; 16], 10_000);
+        let info = secrets(10_000, &B64.encode([7u8; 16]));
+
+        let sealed = sealed_by.encrypt("s3cret pässword").unwrap();
+        assert_ne!(sealed, sealed_by.encrypt("s3cret pässword").unwrap());
+
+        let opened = SecretCipher::open(&info, "correct horse").unwrap();
+        assert_eq!(opened.decrypt(&sealed).unwrap(), "s3cret pässword");
+
+        let wrong = SecretCipher::open(&info, "wrong horse").unwrap();
+        assert_eq!(wrong.decrypt(&sealed).unwrap_err(), "Wrong passphrase or corrupted secrets");
+        assert!(opened.decrypt("bm9wZQ==").is_err());
+        assert!(opened.decrypt("not base64!").is_err());
+    }
+
+    #[test]
+    fn rejects_unsafe_iteration_counts() {
+        let salt = B64.encode([7u8; 16]);
+        for iterations in [0, 1, 9_999, 10_000_001, u32::MAX] {
+            let err = SecretCipher::open(&secrets(iterations, &salt), "pass").err().unwrap();
+            assert!(err.starts_with("Unsupported key derivation iteration count"), "{}", err);
+        }
+        assert!(SecretCipher::open(&secrets(10_000, &salt), "pass").is_ok());
+
+        assert!(SecretCipher::open(&secrets(10_000, "c2hvcnQ="), "pass").is_err());
+        let other = SecretsInfo {
+            kdf: "scrypt".into(),
+            ..secrets(10_000, &salt)
+        };
+        assert!(SecretCipher::open(&other, "pass").is_err());
+    }
+}
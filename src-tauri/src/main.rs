@@ -1,10 +1,53 @@
+mod ansible;
+mod backup;
+mod client_import;
 mod commands;
 mod db;
+mod favorites;
+mod history;
+mod import;
+mod inventory;
+mod migrations;
+mod notes;
+mod remote_edit;
+mod search;
+mod session_log;
+mod settings;
+mod sftp;
+mod ssh_config;
+mod ssh_session;
+mod sync;
+mod tags;
+mod transfer;
+mod trash;
+mod tree;
+mod tunnel;
+mod tunnel_profile;
+mod validation;
 // mod ssh_stream;
 mod ssh_stream_xterm;
 
+use ansible::*;
+use backup::*;
+use client_import::*;
 use commands::*;
 use db::init_db;
+use favorites::*;
+use history::*;
+use inventory::*;
+use notes::*;
+use remote_edit::*;
+use search::*;
+use session_log::*;
+use sftp::*;
+use ssh_config::*;
+use sync::*;
+use tags::*;
+use transfer::*;
+use trash::*;
+use tree::*;
+use tunnel::*;
+use tunnel_profile::*;
 // use ssh_stream::*;
 use ssh_stream_xterm::*;
 use tauri::Manager;
@@ -14,6 +57,10 @@ fn main() {
         .setup(|app| {
             let db = init_db(&app.handle());
             app.manage(db);
+            autostart_on_launch(app.handle());
+            start_auto_snapshots(app.handle());
+            start_trash_auto_purge(app.handle());
+            close_interrupted_sessions(app.handle());
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -23,17 +70,128 @@ fn main() {
             update_group,
             rename_group,
             delete_group,
+            get_tree,
+            get_group_path,
+            move_group,
+            copy_group,
+            reorder_groups,
+            set_group_favorite,
+            get_group_details,
+            update_group_notes,
+            set_group_fields,
             // HOST
             list_hosts_by_group,
             create_host,
             update_host,
             delete_host,
+            get_host_details,
+            update_host_notes,
+            set_host_fields,
+            move_hosts,
+            reorder_hosts,
+            set_host_favorite,
+            list_favorites,
             // SSH (STREAMING)
             ssh_exec_start,
             // ssh_exec_input,
             // ssh_exec_resize,
             ssh_exec_cancel,
-            ssh_exec_input
+            ssh_exec_input,
+            // SESSION LOG
+            get_session_log_config,
+            set_session_log_config,
+            // SFTP
+            sftp_list_dir,
+            sftp_stat,
+            sftp_realpath,
+            sftp_mkdir,
+            sftp_rmdir,
+            sftp_rename,
+            sftp_unlink,
+            sftp_symlink,
+            sftp_readlink,
+            sftp_disconnect,
+            // TRANSFER
+            transfer_upload,
+            transfer_download,
+            transfer_list,
+            transfer_cancel,
+            transfer_pause,
+            transfer_resume,
+            transfer_clear_finished,
+            transfer_get_parallelism,
+            transfer_set_parallelism,
+            // SYNC
+            sync_plan,
+            sync_apply,
+            sync_cancel,
+            // REMOTE EDIT
+            get_remote_editor,
+            set_remote_editor,
+            open_remote_file,
+            list_remote_edits,
+            resolve_remote_edit_conflict,
+            close_remote_edit,
+            // TUNNEL
+            tunnel_start_local,
+            tunnel_start_remote,
+            tunnel_start_dynamic,
+            tunnel_stop,
+            tunnel_list,
+            // TUNNEL PROFILE
+            list_tunnel_profiles,
+            create_tunnel_profile,
+            update_tunnel_profile,
+            delete_tunnel_profile,
+            start_tunnel_profile,
+            stop_tunnel_profile,
+            // IMPORT
+            preview_ssh_config_import,
+            import_ssh_config,
+            preview_client_import,
+            import_client_sessions,
+            preview_ansible_import,
+            import_ansible_inventory,
+            list_import_sources,
+            resync_import_source,
+            delete_import_source,
+            // EXPORT
+            render_ssh_config,
+            export_ssh_config,
+            // INVENTORY
+            export_inventory,
+            import_inventory,
+            // BACKUP
+            backup_database,
+            restore_database,
+            list_snapshots,
+            create_snapshot,
+            get_snapshot_config,
+            set_snapshot_config,
+            // TAGS
+            list_tags,
+            get_host_tags,
+            tag_hosts,
+            untag_hosts,
+            rename_tag,
+            delete_tag,
+            list_hosts_by_tags,
+            select_hosts,
+            // SEARCH
+            search,
+            rebuild_search_index,
+            // TRASH
+            delete_group_recursive,
+            list_trash,
+            restore_trash_item,
+            purge_trash_item,
+            empty_trash,
+            get_trash_policy,
+            set_trash_policy,
+            // HISTORY
+            list_recent_hosts,
+            get_host_history,
+            clear_connection_history
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
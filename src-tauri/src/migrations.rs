@@ -0,0 +1,174 @@
+use rusqlite::{Connection, Transaction};
+use std::{fs, path::Path};
+
+/* =========================
+   MIGRATIONS
+   Append only. Never edit a migration that has shipped, add a new one.
+========================= */
+
+enum Step {
+    Sql(&'static str),
+    Code(fn(&Transaction) -> rusqlite::Result<()>),
+}
+
+struct Migration {
+    version: i64,
+    name: &'static str,
+    step: Step,
+}
+
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial",
+        step: Step::Sql(include_str!("../migrations/0001_initial.sql")),
+    },
+    Migration {
+        version: 2,
+        name: "settings_and_tunnel_profiles",
+        step: Step::Sql(include_str!("../migrations/0002_settings_and_tunnel_profiles.sql")),
+    },
+    Migration {
+        version: 3,
+        name: "host_identity_file_and_proxy_jump",
+        step: Step::Code(host_identity_file_and_proxy_jump),
+    },
+    Migration {
+        version: 4,
+        name: "import_sources",
+        step: Step::Sql(include_str!("../migrations/0004_import_sources.sql")),
+    },
+    Migration {
+        version: 5,
+        name: "tags",
+        step: Step::Sql(include_str!("../migrations/0005_tags.sql")),
+    },
+    Migration {
+        version: 6,
+        name: "search",
+        step: Step::Sql(include_str!("../migrations/0006_search.sql")),
+    },
+    Migration {
+        version: 7,
+        name: "trash",
+        step: Step::Sql(include_str!("../migrations/0007_trash.sql")),
+    },
+    Migration {
+        version: 8,
+        name: "ordering_and_favorites",
+        step: Step::Sql(include_str!("../migrations/0008_ordering_and_favorites.sql")),
+    },
+    Migration {
+        version: 9,
+        name: "connection_history",
+        step: Step::Sql(include_str!("../migrations/0009_connection_history.sql")),
+    },
+    Migration {
+        version: 10,
+        name: "notes_and_custom_fields",
+        step: Step::Sql(include_str!("../migrations/0010_notes_and_custom_fields.sql")),
+    },
+];
+
+pub fn latest_version() -> i64 {
+    MIGRATIONS.last().map_or(0, |m| m.version)
+}
+
+/* =========================
+   RUNNER
+========================= */
+
+/// Brings the database up to `latest_version()`. Each migration runs in its
+/// own transaction together with the `user_version` bump, so a failure leaves
+/// the database at the last good version. Before touching an existing database
+/// a copy is written to `backup_dir`.
+pub fn run_migrations(conn: &mut Connection, backup_dir: &Path) -> Result<(), String> {
+    let current = user_version(conn)?;
+    let latest = latest_version();
+
+    if current > latest {
+        return Err(format!(
+            "Database schema version {} is newer than this app supports ({})",
+            current, latest
+        ));
+    }
+    if current == latest {
+        return Ok(());
+    }
+
+    if has_tables(conn)? {
+        let path = backup_before_upgrade(conn, backup_dir, current)?;
+        println!("[DB] Backup before upgrade written to {}", path);
+    }
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+        println!("[DB] Migrating to v{} ({})", migration.version, migration.name);
+
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+        let applied = match migration.step {
+            Step::Sql(sql) => tx.execute_batch(sql),
+            Step::Code(f) => f(&tx),
+        }
+        .and_then(|_| tx.pragma_update(None, "user_version", migration.version));
+
+        applied.map_err(|e| {
+            format!("Migration {} ({}) failed: {}", migration.version, migration.name, e)
+        })?;
+        tx.commit().map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+fn user_version(conn: &Connection) -> Result<i64, String> {
+    conn.query_row("PRAGMA user_version", [], |r| r.get(0))
+        .map_err(|e| e.to_string())
+}
+
+/// Databases from before the migration runner sit at user_version 0 but have data
+fn has_tables(conn: &Connection) -> Result<bool, String> {
+    conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%')",
+        [],
+        |r| r.get(0),
+    )
+    .map_err(|e| e.to_string())
+}
+
+fn backup_before_upgrade(conn: &Connection, backup_dir: &Path, version: i64) -> Result<String, String> {
+    fs::create_dir_all(backup_dir).map_err(|e| e.to_string())?;
+
+    let path = backup_dir.join(format!(
+        "nethopper-v{}-{}.db",
+        version,
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    let path = path.to_string_lossy().to_string();
+
+    conn.execute("VACUUM INTO ?", [&path])
+        .map_err(|e| format!("Backup before upgrade failed: {}", e))?;
+    Ok(path)
+}
+
+/* =========================
+   CODE MIGRATIONS
+========================= */
+
+/// Development builds added these columns on startup, so they may already exist
+fn host_identity_file_and_proxy_jump(tx: &Transaction) -> rusqlite::Result<()> {
+    add_column_if_missing(tx, "hosts", "identity_file", "TEXT")?;
+    add_column_if_missing(tx, "hosts", "proxy_jump", "TEXT")
+}
+
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, decl: &str) -> rusqlite::Result<()> {
+    let exists = conn
+        .prepare(&format!("PRAGMA table_info({})", table))?
+        .query_map([], |r| r.get::<_, String>(1))?
+        .filter_map(Result::ok)
+        .any(|name| name == column);
+
+    if !exists {
+        conn.execute_batch(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, decl))?;
+    }
+    Ok(())
+}
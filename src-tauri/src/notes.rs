@@ -0,0 +1,224 @@
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use crate::db::Db;
+
+/* =========================
+   MODELS
+========================= */
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CustomField {
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Serialize)]
+pub struct Details {
+    /// Markdown, rendered by the frontend
+    pub notes: Option<String>,
+    /// In the order they were saved
+    pub fields: Vec<CustomField>,
+}
+
+/// Hosts and groups keep notes and fields in the same shape
+#[derive(Clone, Copy)]
+pub enum Owner {
+    Host,
+    Group,
+}
+
+impl Owner {
+    pub fn table(self) -> &'static str {
+        match self {
+            Owner::Host => "hosts",
+            Owner::Group => "groups",
+        }
+    }
+
+    fn fields_table(self) -> (&'static str, &'static str) {
+        match self {
+            Owner::Host => ("host_fields", "host_id"),
+            Owner::Group => ("group_fields", "group_id"),
+        }
+    }
+
+    fn not_found(self) -> String {
+        match self {
+            Owner::Host => "Host not found".into(),
+            Owner::Group => "Group not found".into(),
+        }
+    }
+}
+
+/* =========================
+   TAURI COMMANDS
+========================= */
+
+#[tauri::command]
+pub fn get_host_details(id: i64, db: tauri::State<Db>) -> Result<Details, String> {
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    load_details(&conn, Owner::Host, id)
+}
+
+#[tauri::command]
+pub fn get_group_details(id: i64, db: tauri::State<Db>) -> Result<Details, String> {
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    load_details(&conn, Owner::Group, id)
+}
+
+#[tauri::command]
+pub fn update_host_notes(id: i64, notes: Option<String>, db: tauri::State<Db>) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    save_notes(&conn, Owner::Host, id, notes)
+}
+
+#[tauri::command]
+pub fn update_group_notes(id: i64, notes: Option<String>, db: tauri::State<Db>) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    save_notes(&conn, Owner::Group, id, notes)
+}
+
+/// Replaces all custom fields of the host
+#[tauri::command]
+pub fn set_host_fields(id: i64, fields: Vec<CustomField>, db: tauri::State<Db>) -> Result<(), String> {
+    let mut conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    save_fields(&mut conn, Owner::Host, id, fields)
+}
+
+/// Replaces all custom fields of the group
+#[tauri::command]
+pub fn set_group_fields(id: i64, fields: Vec<CustomField>, db: tauri::State<Db>) -> Result<(), String> {
+    let mut conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    save_fields(&mut conn, Owner::Group, id, fields)
+}
+
+/* =========================
+   HELPERS
+========================= */
+
+fn load_details(conn: &Connection, owner: Owner, id: i64) -> Result<Details, String> {
+    let notes: Option<String> = conn
+        .query_row(
+            &format!("SELECT notes FROM {} WHERE id = ? AND deleted_at IS NULL", owner.table()),
+            [id],
+            |r| r.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| owner.not_found())?;
+
+    let (table, column) = owner.fields_table();
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT key, value FROM {} WHERE {} = ? ORDER BY position, key",
+            table, column
+        ))
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([id], |r| {
+            Ok(CustomField {
+                key: r.get(0)?,
+                value: r.get(1)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(Details {
+        notes,
+        fields: rows.filter_map(Result::ok).collect(),
+    })
+}
+
+fn save_notes(conn: &Connection, owner: Owner, id: i64, notes: Option<String>) -> Result<(), String> {
+    let updated = conn
+        .execute(
+            &format!("UPDATE {} SET notes = ? WHERE id = ? AND deleted_at IS NULL", owner.table()),
+            rusqlite::params![notes.filter(|n| !n.trim().is_empty()), id],
+        )
+        .map_err(|e| e.to_string())?;
+
+    if updated == 0 {
+        return Err(owner.not_found());
+    }
+    Ok(())
+}
+
+fn save_fields(conn: &mut Connection, owner: Owner, id: i64, fields: Vec<CustomField>) -> Result<(), String> {
+    let exists: bool = conn
+        .query_row(
+            &format!(
+                "SELECT EXISTS (SELECT 1 FROM {} WHERE id = ? AND deleted_at IS NULL)",
+                owner.table()
+            ),
+            [id],
+            |r| r.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if !exists {
+        return Err(owner.not_found());
+    }
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    replace_fields(&tx, owner, id, &fields)?;
+    tx.commit().map_err(|e| e.to_string())
+}
+
+/// Validates `fields` and replaces the stored ones. Run inside a transaction.
+pub fn replace_fields(conn: &Connection, owner: Owner, id: i64, fields: &[CustomField]) -> Result<(), String> {
+    let mut seen = HashSet::new();
+    for field in fields {
+        let key = field.key.trim();
+        if key.is_empty() {
+            return Err("Field name cannot be empty".into());
+        }
+        if !seen.insert(key.to_lowercase()) {
+            return Err(format!("Field '{}' is listed twice", key));
+        }
+    }
+
+    let (table, column) = owner.fields_table();
+    conn.execute(&format!("DELETE FROM {} WHERE {} = ?", table, column), [id])
+        .map_err(|e| e.to_string())?;
+    for (position, field) in fields.iter().enumerate() {
+        conn.execute(
+            &format!(
+                "INSERT INTO {} ({}, key, value, position) VALUES (?, ?, ?, ?)",
+                table, column
+            ),
+            rusqlite::params![id, field.key.trim(), field.value, position as i64],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Custom fields of every host or every group, keyed by owner id
+pub fn load_all_fields(conn: &Connection, owner: Owner) -> Result<HashMap<i64, Vec<CustomField>>, String> {
+    let (table, column) = owner.fields_table();
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {col}, key, value FROM {table} ORDER BY {col}, position, key",
+            col = column,
+            table = table
+        ))
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |r| {
+            Ok((
+                r.get::<_, i64>(0)?,
+                CustomField {
+                    key: r.get(1)?,
+                    value: r.get(2)?,
+                },
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut out: HashMap<i64, Vec<CustomField>> = HashMap::new();
+    for (id, field) in rows.filter_map(Result::ok) {
+        out.entry(id).or_default().push(field);
+    }
+    Ok(out)
+}
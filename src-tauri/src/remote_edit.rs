@@ -0,0 +1,438 @@
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use ssh2::{OpenFlags, OpenType};
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, SystemTime},
+};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::db::Db;
+use crate::settings::{get_setting, set_setting};
+use crate::sftp::with_sftp;
+
+/* =========================
+   CONFIG
+========================= */
+
+const EDITOR_KEY: &str = "remote_edit.editor";
+const POLL_INTERVAL_MS: u64 = 1000;
+/// Editors often write in several steps; wait for the file to settle before uploading
+const SETTLE_MS: u64 = 300;
+const DEFAULT_FILE_MODE: i32 = 0o644;
+
+/* =========================
+   REGISTRY
+========================= */
+
+static EDITS: Lazy<Mutex<HashMap<String, Arc<RemoteEdit>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+static EDIT_SEQ: AtomicU64 = AtomicU64::new(1);
+
+/* =========================
+   MODELS
+========================= */
+
+#[derive(Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RemoteEditStatus {
+    Synced,
+    Uploading,
+    Conflict,
+    Error,
+}
+
+#[derive(Serialize, Clone)]
+pub struct RemoteEditInfo {
+    pub edit_id: String,
+    pub host_id: i64,
+    pub remote_path: String,
+    pub local_path: String,
+    pub status: RemoteEditStatus,
+    pub uploads: u64,
+    pub error: Option<String>,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictResolution {
+    /// Upload the local copy over the remote changes
+    Overwrite,
+    /// Replace the local copy with the current remote file
+    Reload,
+}
+
+/// Remote and local state as of the last download/upload
+#[derive(Clone, Copy, PartialEq)]
+struct Baseline {
+    remote_mtime: Option<u64>,
+    remote_size: Option<u64>,
+    local_mtime: Option<SystemTime>,
+    local_len: u64,
+}
+
+struct RemoteEdit {
+    info: Mutex<RemoteEditInfo>,
+    baseline: Mutex<Baseline>,
+    stop: AtomicBool,
+}
+
+impl RemoteEdit {
+    fn snapshot(&self) -> RemoteEditInfo {
+        self.info.lock().unwrap().clone()
+    }
+}
+
+/* =========================
+   EVENTS
+========================= */
+
+#[derive(Serialize, Clone)]
+struct RemoteEditEvent {
+    edit_id: String,
+    remote_path: String,
+    status: RemoteEditStatus,
+    error: Option<String>,
+}
+
+/* =========================
+   TAURI COMMANDS
+========================= */
+
+#[tauri::command]
+pub fn get_remote_editor(db: tauri::State<Db>) -> Result<Option<String>, String> {
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    get_setting(&conn, EDITOR_KEY)
+}
+
+/// `editor` is a program name or path; `None` uses the system default for the file type
+#[tauri::command]
+pub fn set_remote_editor(editor: Option<String>, db: tauri::State<Db>) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    let editor = editor.filter(|e| !e.trim().is_empty());
+    set_setting(&conn, EDITOR_KEY, &editor)
+}
+
+#[tauri::command]
+pub fn open_remote_file(
+    host_id: i64,
+    remote_path: String,
+    app: AppHandle,
+) -> Result<RemoteEditInfo, String> {
+    let editor: Option<String> = {
+        let db = app.state::<Db>();
+        let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+        get_setting::<Option<String>>(&conn, EDITOR_KEY)?.flatten()
+    };
+
+    // the same file is already open: just bring the editor back
+    let existing = EDITS
+        .lock()
+        .unwrap()
+        .values()
+        .find(|e| {
+            let info = e.snapshot();
+            info.host_id == host_id && info.remote_path == remote_path
+        })
+        .cloned();
+    if let Some(edit) = existing {
+        let info = edit.snapshot();
+        open_in_editor(&info.local_path, editor.as_deref())?;
+        return Ok(info);
+    }
+
+    let edit_id = format!(
+        "edit-{}-{}",
+        chrono::Utc::now().timestamp_millis(),
+        EDIT_SEQ.fetch_add(1, Ordering::Relaxed)
+    );
+
+    let file_name = Path::new(&remote_path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or("Not a file path")?;
+
+    let workspace = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| e.to_string())?
+        .join("remote-edit")
+        .join(&edit_id);
+    fs::create_dir_all(&workspace).map_err(|e| e.to_string())?;
+    let local_path = workspace.join(file_name);
+
+    let baseline = match download(&app, host_id, &remote_path, &local_path) {
+        Ok(b) => b,
+        Err(e) => {
+            fs::remove_dir_all(&workspace).ok();
+            return Err(e);
+        }
+    };
+
+    let edit = Arc::new(RemoteEdit {
+        info: Mutex::new(RemoteEditInfo {
+            edit_id: edit_id.clone(),
+            host_id,
+            remote_path,
+            local_path: local_path.to_string_lossy().to_string(),
+            status: RemoteEditStatus::Synced,
+            uploads: 0,
+            error: None,
+        }),
+        baseline: Mutex::new(baseline),
+        stop: AtomicBool::new(false),
+    });
+
+    EDITS.lock().unwrap().insert(edit_id, edit.clone());
+
+    let watcher_app = app.clone();
+    let watcher_edit = edit.clone();
+    std::thread::spawn(move || watch_worker(watcher_app, watcher_edit));
+
+    let info = edit.snapshot();
+    open_in_editor(&info.local_path, editor.as_deref())?;
+    Ok(info)
+}
+
+#[tauri::command]
+pub fn list_remote_edits() -> Vec<RemoteEditInfo> {
+    EDITS
+        .lock()
+        .unwrap()
+        .values()
+        .map(|e| e.snapshot())
+        .collect()
+}
+
+#[tauri::command]
+pub fn resolve_remote_edit_conflict(
+    edit_id: String,
+    resolution: ConflictResolution,
+    app: AppHandle,
+) -> Result<(), String> {
+    let edit = find_edit(&edit_id)?;
+    let info = edit.snapshot();
+
+    if info.status != RemoteEditStatus::Conflict {
+        return Err("No conflict to resolve".into());
+    }
+
+    let result = match resolution {
+        ConflictResolution::Overwrite => upload(&app, &info, Path::new(&info.local_path)),
+        ConflictResolution::Reload => download(
+            &app,
+            info.host_id,
+            &info.remote_path,
+            Path::new(&info.local_path),
+        ),
+    };
+
+    match result {
+        Ok(b) => {
+            *edit.baseline.lock().unwrap() = b;
+            if matches!(resolution, ConflictResolution::Overwrite) {
+                edit.info.lock().unwrap().uploads += 1;
+            }
+            set_status(&app, &edit, RemoteEditStatus::Synced, None);
+            Ok(())
+        }
+        Err(e) => {
+            set_status(&app, &edit, RemoteEditStatus::Error, Some(e.clone()));
+            Err(e)
+        }
+    }
+}
+
+/// Stops watching and removes the local copy
+#[tauri::command]
+pub fn close_remote_edit(edit_id: String) -> Result<(), String> {
+    let edit = EDITS
+        .lock()
+        .unwrap()
+        .remove(&edit_id)
+        .ok_or("Remote edit not found")?;
+
+    edit.stop.store(true, Ordering::Relaxed);
+
+    if let Some(dir) = Path::new(&edit.snapshot().local_path).parent() {
+        fs::remove_dir_all(dir).ok();
+    }
+    Ok(())
+}
+
+/* =========================
+   WATCHER
+========================= */
+
+fn watch_worker(app: AppHandle, edit: Arc<RemoteEdit>) {
+    let local_path = PathBuf::from(edit.snapshot().local_path);
+
+    while !edit.stop.load(Ordering::Relaxed) {
+        std::thread::sleep(Duration::from_millis(POLL_INTERVAL_MS));
+
+        let Some((mtime, len)) = local_state(&local_path) else {
+            continue;
+        };
+
+        let baseline = *edit.baseline.lock().unwrap();
+        if baseline.local_mtime == mtime && baseline.local_len == len {
+            continue;
+        }
+
+        // a conflict stays parked until the user resolves it
+        if edit.snapshot().status == RemoteEditStatus::Conflict {
+            continue;
+        }
+
+        std::thread::sleep(Duration::from_millis(SETTLE_MS));
+        if local_state(&local_path) != Some((mtime, len)) {
+            continue;
+        }
+
+        sync_local_change(&app, &edit, &local_path, baseline);
+    }
+}
+
+fn sync_local_change(app: &AppHandle, edit: &RemoteEdit, local_path: &Path, baseline: Baseline) {
+    let info = edit.snapshot();
+
+    let remote = with_sftp(app, info.host_id, |sftp| {
+        sftp.stat(Path::new(&info.remote_path))
+    });
+
+    let remote_changed = match &remote {
+        Ok(stat) => stat.mtime != baseline.remote_mtime || stat.size != baseline.remote_size,
+        // file removed on the server meanwhile
+        Err(_) => true,
+    };
+
+    if remote_changed {
+        set_status(
+            app,
+            edit,
+            RemoteEditStatus::Conflict,
+            Some("Remote file changed since it was opened".into()),
+        );
+        return;
+    }
+
+    set_status(app, edit, RemoteEditStatus::Uploading, None);
+
+    match upload(app, &info, local_path) {
+        Ok(b) => {
+            *edit.baseline.lock().unwrap() = b;
+            edit.info.lock().unwrap().uploads += 1;
+            set_status(app, edit, RemoteEditStatus::Synced, None);
+        }
+        Err(e) => {
+            // keep the old baseline so the next poll retries the upload
+            set_status(app, edit, RemoteEditStatus::Error, Some(e));
+        }
+    }
+}
+
+/* =========================
+   HELPERS
+========================= */
+
+fn download(
+    app: &AppHandle,
+    host_id: i64,
+    remote_path: &str,
+    local_path: &Path,
+) -> Result<Baseline, String> {
+    let remote = Path::new(remote_path);
+
+    let (stat, mut src) = with_sftp(app, host_id, |sftp| {
+        Ok((sftp.stat(remote)?, sftp.open(remote)?))
+    })?;
+
+    let mut dst = fs::File::create(local_path).map_err(|e| e.to_string())?;
+    io::copy(&mut src, &mut dst).map_err(|e| e.to_string())?;
+    dst.flush().map_err(|e| e.to_string())?;
+    drop(dst);
+
+    let (local_mtime, local_len) = local_state(local_path).ok_or("Cannot read local copy")?;
+
+    Ok(Baseline {
+        remote_mtime: stat.mtime,
+        remote_size: stat.size,
+        local_mtime,
+        local_len,
+    })
+}
+
+fn upload(app: &AppHandle, info: &RemoteEditInfo, local_path: &Path) -> Result<Baseline, String> {
+    let data = fs::read(local_path).map_err(|e| e.to_string())?;
+    let (local_mtime, local_len) = local_state(local_path).ok_or("Cannot read local copy")?;
+    let remote = Path::new(&info.remote_path);
+
+    let mut dst = with_sftp(app, info.host_id, |sftp| {
+        sftp.open_mode(
+            remote,
+            OpenFlags::WRITE | OpenFlags::CREATE | OpenFlags::TRUNCATE,
+            DEFAULT_FILE_MODE,
+            OpenType::File,
+        )
+    })?;
+    dst.write_all(&data).map_err(|e| e.to_string())?;
+    drop(dst);
+
+    let stat = with_sftp(app, info.host_id, |sftp| sftp.stat(remote))?;
+
+    Ok(Baseline {
+        remote_mtime: stat.mtime,
+        remote_size: stat.size,
+        local_mtime,
+        local_len,
+    })
+}
+
+fn local_state(path: &Path) -> Option<(Option<SystemTime>, u64)> {
+    fs::metadata(path).ok().map(|m| (m.modified().ok(), m.len()))
+}
+
+fn open_in_editor(path: &str, editor: Option<&str>) -> Result<(), String> {
+    tauri_plugin_opener::open_path(path, editor).map_err(|e| format!("Cannot open editor: {}", e))
+}
+
+fn find_edit(edit_id: &str) -> Result<Arc<RemoteEdit>, String> {
+    EDITS
+        .lock()
+        .unwrap()
+        .get(edit_id)
+        .cloned()
+        .ok_or_else(|| "Remote edit not found".to_string())
+}
+
+fn set_status(
+    app: &AppHandle,
+    edit: &RemoteEdit,
+    status: RemoteEditStatus,
+    error: Option<String>,
+) {
+    let info = {
+        let mut info = edit.info.lock().unwrap();
+        info.status = status;
+        info.error = error.clone();
+        info.clone()
+    };
+
+    let _ = app.emit(
+        "remote_edit:status",
+        RemoteEditEvent {
+            edit_id: info.edit_id,
+            remote_path: info.remote_path,
+            status,
+            error,
+        },
+    );
+}
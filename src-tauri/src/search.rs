@@ -0,0 +1,276 @@
+use rusqlite::Connection;
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::db::Db;
+
+/* =========================
+   CONFIG
+========================= */
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 500;
+
+/// bm25 weights in `search_index` column order:
+/// name, address, username, tags, notes, fields, group_path
+const RANK_WEIGHTS: &str = "10.0, 6.0, 3.0, 5.0, 1.0, 2.0, 2.0";
+
+/* =========================
+   MODELS
+========================= */
+
+#[derive(Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchKind {
+    Host,
+    Group,
+}
+
+#[derive(Serialize, Clone)]
+pub struct Breadcrumb {
+    pub id: i64,
+    pub name: String,
+}
+
+#[derive(Serialize)]
+pub struct SearchResult {
+    pub kind: SearchKind,
+    pub id: i64,
+    pub name: String,
+    /// Host address, empty for groups
+    pub address: String,
+    pub username: String,
+    /// Groups from the root down to the containing group
+    pub breadcrumb: Vec<Breadcrumb>,
+    /// Lower is better (bm25)
+    pub rank: f64,
+}
+
+/* =========================
+   TAURI COMMANDS
+========================= */
+
+/// Every word of `query` must match the start of a word in any indexed column
+#[tauri::command]
+pub fn search(query: String, limit: Option<i64>, db: tauri::State<Db>) -> Result<Vec<SearchResult>, String> {
+    let Some(fts_query) = to_fts_query(&query) else {
+        return Ok(Vec::new());
+    };
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    refresh_search_index(&conn)?;
+
+    let sql = format!(
+        "SELECT s.kind, s.ref_id, s.name, s.address, s.username,
+                COALESCE(h.group_id, g.parent_id), bm25(search_index, {}) AS rank
+         FROM search_index s
+         LEFT JOIN hosts h ON s.kind = 'host' AND h.id = s.ref_id
+         LEFT JOIN groups g ON s.kind = 'group' AND g.id = s.ref_id
+         WHERE search_index MATCH ?1
+         ORDER BY rank
+         LIMIT ?2",
+        RANK_WEIGHTS
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+
+    let groups = load_group_names(&conn)?;
+
+    let rows = stmt
+        .query_map(rusqlite::params![fts_query, limit], |r| {
+            let kind: String = r.get(0)?;
+            Ok(SearchResult {
+                kind: if kind == "group" { SearchKind::Group } else { SearchKind::Host },
+                id: r.get(1)?,
+                name: r.get(2)?,
+                address: r.get::<_, Option<String>>(3)?.unwrap_or_default(),
+                username: r.get::<_, Option<String>>(4)?.unwrap_or_default(),
+                breadcrumb: breadcrumb(&groups, r.get(5)?),
+                rank: r.get(6)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.filter_map(Result::ok).collect())
+}
+
+/// Forces a full rebuild, e.g. after restoring a backup
+#[tauri::command]
+pub fn rebuild_search_index(db: tauri::State<Db>) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    conn.execute("UPDATE search_index_state SET dirty = 1", [])
+        .map_err(|e| e.to_string())?;
+    refresh_search_index(&conn)
+}
+
+/* =========================
+   INDEX
+========================= */
+
+/// Triggers on hosts, groups, tags and custom fields set the dirty flag; the
+/// index is rebuilt lazily on the next search. A full rebuild keeps group paths
+/// of every descendant correct after a rename or move.
+pub fn refresh_search_index(conn: &Connection) -> Result<(), String> {
+    let dirty: bool = conn
+        .query_row("SELECT dirty FROM search_index_state WHERE id = 1", [], |r| r.get(0))
+        .map_err(|e| e.to_string())?;
+    if !dirty {
+        return Ok(());
+    }
+
+    conn.execute_batch(
+        "BEGIN;
+         DELETE FROM search_index;
+
+         WITH RECURSIVE paths(id, path) AS (
+             SELECT id, name FROM groups WHERE parent_id IS NULL
+             UNION ALL
+             SELECT g.id, p.path || ' / ' || g.name FROM groups g JOIN paths p ON g.parent_id = p.id
+         )
+         INSERT INTO search_index (kind, ref_id, name, address, username, tags, notes, fields, group_path)
+         SELECT 'host', h.id, h.name, h.host, h.username,
+                (SELECT group_concat(t.name, ' ') FROM host_tags ht JOIN tags t ON t.id = ht.tag_id
+                 WHERE ht.host_id = h.id),
+                h.notes,
+                (SELECT group_concat(f.key || ' ' || f.value, ' ') FROM host_fields f WHERE f.host_id = h.id),
+                p.path
+         FROM hosts h LEFT JOIN paths p ON p.id = h.group_id
+         WHERE h.deleted_at IS NULL;
+
+         WITH RECURSIVE paths(id, path) AS (
+             SELECT id, name FROM groups WHERE parent_id IS NULL
+             UNION ALL
+             SELECT g.id, p.path || ' / ' || g.name FROM groups g JOIN paths p ON g.parent_id = p.id
+         )
+         INSERT INTO search_index (kind, ref_id, name, notes, fields, group_path)
+         SELECT 'group', g.id, g.name, g.notes,
+                (SELECT group_concat(f.key || ' ' || f.value, ' ') FROM group_fields f WHERE f.group_id = g.id),
+                p.path
+         FROM groups g LEFT JOIN paths p ON p.id = g.parent_id
+         WHERE g.deleted_at IS NULL;
+
+         UPDATE search_index_state SET dirty = 0;
+         COMMIT;",
+    )
+    .map_err(|e| {
+        let _ = conn.execute_batch("ROLLBACK");
+        e.to_string()
+    })
+}
+
+/* =========================
+   HELPERS
+========================= */
+
+/// `web pro` -> `"web"* AND "pro"*`; quoting keeps FTS5 syntax characters
+/// in user input from being interpreted. Words without letters or digits are
+/// dropped, the tokenizer leaves nothing of them and an empty phrase never matches.
+fn to_fts_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .filter(|word| word.chars().any(char::is_alphanumeric))
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" AND "))
+    }
+}
+
+fn load_group_names(conn: &Connection) -> Result<HashMap<i64, (String, Option<i64>)>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id, name, parent_id FROM groups")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |r| Ok((r.get(0)?, (r.get(1)?, r.get(2)?))))
+        .map_err(|e| e.to_string())?;
+    Ok(rows.filter_map(Result::ok).collect())
+}
+
+fn breadcrumb(groups: &HashMap<i64, (String, Option<i64>)>, group_id: Option<i64>) -> Vec<Breadcrumb> {
+    let mut path = Vec::new();
+    let mut current = group_id;
+
+    while let Some(id) = current {
+        // guards against a corrupted tree with a cycle
+        if path.iter().any(|b: &Breadcrumb| b.id == id) {
+            break;
+        }
+        let Some((name, parent)) = groups.get(&id) else {
+            break;
+        };
+        path.push(Breadcrumb {
+            id,
+            name: name.clone(),
+        });
+        current = *parent;
+    }
+
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(text: &str, query: &str) -> bool {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE VIRTUAL TABLE t USING fts5(body, tokenize = 'unicode61 remove_diacritics 2');")
+            .unwrap();
+        conn.execute("INSERT INTO t (body) VALUES (?)", [text]).unwrap();
+        let fts = to_fts_query(query).unwrap();
+        conn.query_row("SELECT COUNT(*) FROM t WHERE t MATCH ?", [fts], |r| r.get::<_, i64>(0))
+            .unwrap()
+            > 0
+    }
+
+    #[test]
+    fn terms_are_quoted_prefixes_joined_with_and() {
+        assert_eq!(to_fts_query("web prod").as_deref(), Some("\"web\"* AND \"prod\"*"));
+        assert_eq!(to_fts_query("  web\t\nprod  ").as_deref(), Some("\"web\"* AND \"prod\"*"));
+        assert_eq!(to_fts_query(""), None);
+        assert_eq!(to_fts_query("   "), None);
+    }
+
+    #[test]
+    fn quotes_are_escaped() {
+        assert_eq!(to_fts_query("say\"hi").as_deref(), Some("\"say\"\"hi\"*"));
+        assert_eq!(to_fts_query("it's").as_deref(), Some("\"it's\"*"));
+        assert!(matches("say hi there", "say\"hi"));
+        assert!(matches("say hi there", "\"say hi\""));
+    }
+
+    #[test]
+    fn operators_are_searched_as_text() {
+        for query in ["NEAR", "NEAR(a b)", "a OR", "NOT", "-db", "web*", "col:web", "^web", "(web", "web)"] {
+            let fts = to_fts_query(query).unwrap();
+            let conn = Connection::open_in_memory().unwrap();
+            conn.execute_batch("CREATE VIRTUAL TABLE t USING fts5(body);").unwrap();
+            let result = conn.query_row("SELECT COUNT(*) FROM t WHERE t MATCH ?", [&fts], |r| r.get::<_, i64>(0));
+            assert!(result.is_ok(), "{} -> {}: {:?}", query, fts, result);
+        }
+
+        assert!(matches("near the edge", "NEAR"));
+        assert!(matches("db-prod-01", "-db"));
+        assert!(!matches("web-01", "-db"));
+        assert!(matches("web01", "web*"));
+        assert!(matches("router or switch", "switch OR"));
+    }
+
+    #[test]
+    fn punctuation_only_words_are_dropped() {
+        assert_eq!(to_fts_query("- \" * + ()"), None);
+        assert_eq!(to_fts_query("web -").as_deref(), Some("\"web\"*"));
+        assert!(matches("web server", "web - *"));
+    }
+
+    #[test]
+    fn prefix_and_diacritics() {
+        assert!(matches("postgres primary", "post prim"));
+        assert!(!matches("postgres primary", "post replica"));
+        assert!(matches("Zürich datacenter", "zurich"));
+    }
+}
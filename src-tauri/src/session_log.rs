@@ -0,0 +1,361 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+use tauri::{AppHandle, Manager};
+
+use crate::db::Db;
+use crate::settings::{get_setting, set_setting};
+
+/* =========================
+   CONFIG
+========================= */
+
+const SETTINGS_KEY: &str = "session_log";
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct SessionLogConfig {
+    pub enabled: bool,
+    /// Folder for log files, defaults to `<app log dir>/sessions`
+    pub directory: Option<String>,
+    /// Placeholders: {host} {address} {port} {user} {host_id} {date} {time}
+    pub filename_template: String,
+    pub timestamps: bool,
+    /// Rotate once the file grows past this size, 0 disables rotation
+    pub max_file_bytes: u64,
+    /// Number of rotated files kept next to the active one
+    pub max_files: u32,
+}
+
+impl Default for SessionLogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: None,
+            filename_template: "{host}_{date}_{time}.log".into(),
+            timestamps: true,
+            max_file_bytes: 10 * 1024 * 1024,
+            max_files: 5,
+        }
+    }
+}
+
+pub struct LogHost {
+    pub id: i64,
+    pub name: String,
+    pub address: String,
+    pub port: i64,
+    pub username: String,
+}
+
+/* =========================
+   TAURI COMMANDS
+========================= */
+
+#[tauri::command]
+pub fn get_session_log_config(db: tauri::State<Db>) -> Result<SessionLogConfig, String> {
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    Ok(get_setting(&conn, SETTINGS_KEY)?.unwrap_or_default())
+}
+
+#[tauri::command]
+pub fn set_session_log_config(
+    config: SessionLogConfig,
+    db: tauri::State<Db>,
+) -> Result<(), String> {
+    if config.filename_template.trim().is_empty() {
+        return Err("Filename template is required".into());
+    }
+
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    set_setting(&conn, SETTINGS_KEY, &config)
+}
+
+/* =========================
+   LOGGER
+========================= */
+
+pub struct SessionLogger {
+    path: PathBuf,
+    file: BufWriter<File>,
+    config: SessionLogConfig,
+    written: u64,
+    at_line_start: bool,
+    stripper: AnsiStripper,
+}
+
+impl SessionLogger {
+    /// Opens a log file for the host, or returns `None` when logging is disabled.
+    pub fn open(app: &AppHandle, host: &LogHost) -> Result<Option<Self>, String> {
+        let config: SessionLogConfig = {
+            let db = app.state::<Db>();
+            let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+            get_setting(&conn, SETTINGS_KEY)?.unwrap_or_default()
+        };
+
+        if !config.enabled {
+            return Ok(None);
+        }
+
+        let dir = match &config.directory {
+            Some(d) if !d.trim().is_empty() => PathBuf::from(d),
+            _ => app
+                .path()
+                .app_log_dir()
+                .map_err(|e| e.to_string())?
+                .join("sessions"),
+        };
+        fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+        let path = dir.join(render_filename(&config.filename_template, host));
+        let file = open_append(&path)?;
+        let written = file.get_ref().metadata().map(|m| m.len()).unwrap_or(0);
+
+        Ok(Some(Self {
+            path,
+            file,
+            config,
+            written,
+            at_line_start: true,
+            stripper: AnsiStripper::default(),
+        }))
+    }
+
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    pub fn write(&mut self, data: &[u8]) -> Result<(), String> {
+        let text = self.stripper.feed(data);
+
+        for line in text.split_inclusive(|b| *b == b'\n') {
+            if self.at_line_start && self.config.timestamps {
+                let stamp = chrono::Local::now()
+                    .format("[%Y-%m-%d %H:%M:%S] ")
+                    .to_string();
+                self.put(stamp.as_bytes())?;
+            }
+
+            self.put(line)?;
+            self.at_line_start = line.ends_with(b"\n");
+
+            if self.at_line_start && self.should_rotate() {
+                self.rotate()?;
+            }
+        }
+
+        self.file.flush().map_err(|e| e.to_string())
+    }
+
+    fn put(&mut self, bytes: &[u8]) -> Result<(), String> {
+        self.file.write_all(bytes).map_err(|e| e.to_string())?;
+        self.written += bytes.len() as u64;
+        Ok(())
+    }
+
+    fn should_rotate(&self) -> bool {
+        self.config.max_file_bytes > 0 && self.written >= self.config.max_file_bytes
+    }
+
+    fn rotate(&mut self) -> Result<(), String> {
+        self.file.flush().map_err(|e| e.to_string())?;
+
+        // file.log.N-1 -> file.log.N ... file.log -> file.log.1
+        let keep = self.config.max_files;
+        if keep == 0 {
+            fs::remove_file(&self.path).ok();
+        } else {
+            fs::remove_file(rotated_path(&self.path, keep)).ok();
+            for n in (1..keep).rev() {
+                fs::rename(rotated_path(&self.path, n), rotated_path(&self.path, n + 1)).ok();
+            }
+            fs::rename(&self.path, rotated_path(&self.path, 1)).map_err(|e| e.to_string())?;
+        }
+
+        self.file = open_append(&self.path)?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+/* =========================
+   HELPERS
+========================= */
+
+fn open_append(path: &Path) -> Result<BufWriter<File>, String> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map(BufWriter::new)
+        .map_err(|e| format!("Cannot open session log {}: {}", path.display(), e))
+}
+
+fn rotated_path(path: &Path, n: u32) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", n));
+    PathBuf::from(name)
+}
+
+fn render_filename(template: &str, host: &LogHost) -> String {
+    let now = chrono::Local::now();
+
+    template
+        .replace("{host}", &sanitize(&host.name))
+        .replace("{address}", &sanitize(&host.address))
+        .replace("{port}", &host.port.to_string())
+        .replace("{user}", &sanitize(&host.username))
+        .replace("{host_id}", &host.id.to_string())
+        .replace("{date}", &now.format("%Y-%m-%d").to_string())
+        .replace("{time}", &now.format("%H%M%S").to_string())
+}
+
+fn sanitize(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect()
+}
+
+/* =========================
+   ANSI STRIPPER
+   Keeps state between chunks so escape sequences split across reads are still removed
+========================= */
+
+#[derive(Default, Clone, Copy, PartialEq)]
+enum AnsiState {
+    #[default]
+    Ground,
+    Escape,
+    Csi,
+    Osc,
+    OscEscape,
+    Charset,
+}
+
+#[derive(Default)]
+pub struct AnsiStripper {
+    state: AnsiState,
+}
+
+impl AnsiStripper {
+    pub fn feed(&mut self, data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(data.len());
+
+        for &b in data {
+            self.state = match self.state {
+                AnsiState::Ground => match b {
+                    0x1b => AnsiState::Escape,
+                    b'\n' | b'\t' => {
+                        out.push(b);
+                        AnsiState::Ground
+                    }
+                    // \r, backspace, bell and other C0 controls carry no text
+                    0x00..=0x1f | 0x7f => AnsiState::Ground,
+                    _ => {
+                        out.push(b);
+                        AnsiState::Ground
+                    }
+                },
+                AnsiState::Escape => match b {
+                    b'[' => AnsiState::Csi,
+                    b']' | b'P' | b'X' | b'^' | b'_' => AnsiState::Osc,
+                    b'(' | b')' | b'*' | b'+' | b'#' | b'%' => AnsiState::Charset,
+                    _ => AnsiState::Ground,
+                },
+                AnsiState::Csi => match b {
+                    0x40..=0x7e => AnsiState::Ground,
+                    _ => AnsiState::Csi,
+                },
+                AnsiState::Osc => match b {
+                    0x07 => AnsiState::Ground,
+                    0x1b => AnsiState::OscEscape,
+                    _ => AnsiState::Osc,
+                },
+                AnsiState::OscEscape => match b {
+                    b'\\' => AnsiState::Ground,
+                    _ => AnsiState::Osc,
+                },
+                AnsiState::Charset => AnsiState::Ground,
+            };
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AnsiStripper;
+
+    fn strip_chunks(chunks: &[&[u8]]) -> String {
+        let mut stripper = AnsiStripper::default();
+        let out: Vec<u8> = chunks.iter().flat_map(|c| stripper.feed(c)).collect();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn keeps_plain_text_newlines_and_tabs() {
+        assert_eq!(strip_chunks(&[b"ls -la\n\tfoo\n"]), "ls -la\n\tfoo\n");
+    }
+
+    #[test]
+    fn drops_carriage_return_backspace_and_bell() {
+        assert_eq!(strip_chunks(&[b"ab\x08c\r\n\x07done"]), "abc\ndone");
+    }
+
+    #[test]
+    fn strips_csi_sequences() {
+        assert_eq!(strip_chunks(&[b"\x1b[1;32mgreen\x1b[0m \x1b[2K\x1b[?25lx"]), "green x");
+    }
+
+    #[test]
+    fn strips_csi_split_across_chunks() {
+        assert_eq!(strip_chunks(&[b"a\x1b", b"[1;3", b"1m", b"b"]), "ab");
+        assert_eq!(strip_chunks(&[b"a\x1b[", b"0", b"m", b"b"]), "ab");
+    }
+
+    #[test]
+    fn lone_escape_at_chunk_end_waits_for_next_chunk() {
+        let mut stripper = AnsiStripper::default();
+        assert_eq!(stripper.feed(b"prompt$ \x1b"), b"prompt$ ");
+        assert_eq!(stripper.feed(b"[Kls\n"), b"ls\n");
+    }
+
+    #[test]
+    fn strips_osc_terminated_by_bell() {
+        assert_eq!(strip_chunks(&[b"\x1b]0;user@host: ~\x07$ "]), "$ ");
+    }
+
+    #[test]
+    fn strips_osc_terminated_by_st_split_across_chunks() {
+        assert_eq!(strip_chunks(&[b"\x1b]2;ti", b"tle\x1b", b"\\", b"text"]), "text");
+    }
+
+    #[test]
+    fn escape_inside_osc_without_backslash_stays_in_osc() {
+        assert_eq!(strip_chunks(&[b"\x1b]0;a\x1bb\x07ok"]), "ok");
+    }
+
+    #[test]
+    fn strips_dcs_and_charset_designation() {
+        assert_eq!(strip_chunks(&[b"\x1bPq#0\x1b\\x\x1b(By\x1b", b")0z"]), "xyz");
+    }
+
+    #[test]
+    fn two_byte_escape_is_dropped() {
+        assert_eq!(strip_chunks(&[b"a\x1b=b\x1b", b">c"]), "abc");
+    }
+
+    #[test]
+    fn passes_utf8_through() {
+        assert_eq!(strip_chunks(&["héllo \x1b[1m✓\x1b[0m".as_bytes()]), "héllo ✓");
+    }
+}
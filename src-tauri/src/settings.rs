@@ -0,0 +1,38 @@
+use rusqlite::{Connection, OptionalExtension};
+use serde::{de::DeserializeOwned, Serialize};
+
+/* =========================
+   KEY / VALUE SETTINGS
+   Values are stored as JSON in the `settings` table
+========================= */
+
+pub fn get_setting<T: DeserializeOwned>(conn: &Connection, key: &str) -> Result<Option<T>, String> {
+    let raw: Option<String> = conn
+        .query_row(
+            "SELECT value FROM settings WHERE key = ?",
+            [key],
+            |r| r.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    match raw {
+        Some(v) => serde_json::from_str(&v)
+            .map(Some)
+            .map_err(|e| format!("Invalid setting '{}': {}", key, e)),
+        None => Ok(None),
+    }
+}
+
+pub fn set_setting<T: Serialize>(conn: &Connection, key: &str, value: &T) -> Result<(), String> {
+    let raw = serde_json::to_string(value).map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "INSERT INTO settings (key, value) VALUES (?1, ?2)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = CURRENT_TIMESTAMP",
+        rusqlite::params![key, raw],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
@@ -0,0 +1,289 @@
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use ssh2::{ErrorCode, FileStat, RenameFlags, Sftp};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+use tauri::AppHandle;
+
+use crate::ssh_session::connect_host;
+
+/* =========================
+   CONFIG
+========================= */
+
+const DEFAULT_DIR_MODE: i32 = 0o755;
+
+// libssh2 SFTP status codes we translate for the UI
+const FX_NO_SUCH_FILE: i32 = 2;
+const FX_PERMISSION_DENIED: i32 = 3;
+const FX_FILE_ALREADY_EXISTS: i32 = 11;
+const FX_DIR_NOT_EMPTY: i32 = 18;
+
+/* =========================
+   CONNECTION POOL
+   One SFTP channel per host, shared by every command targeting it
+========================= */
+
+static SFTP_POOL: Lazy<Mutex<HashMap<i64, Arc<Sftp>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn pooled_sftp(app: &AppHandle, host_id: i64) -> Result<Arc<Sftp>, String> {
+    if let Some(sftp) = SFTP_POOL.lock().unwrap().get(&host_id) {
+        return Ok(sftp.clone());
+    }
+
+    let sess = connect_host(app, host_id)?;
+    let sftp = Arc::new(sess.sftp().map_err(|e| format!("SFTP init failed: {}", e))?);
+
+    SFTP_POOL.lock().unwrap().insert(host_id, sftp.clone());
+    Ok(sftp)
+}
+
+fn drop_sftp(host_id: i64) {
+    SFTP_POOL.lock().unwrap().remove(&host_id);
+}
+
+/// Runs `op` on the host's pooled SFTP channel. A session-level failure (dropped
+/// connection, timeout) evicts the channel and the operation is retried once on a fresh one.
+pub fn with_sftp<T>(
+    app: &AppHandle,
+    host_id: i64,
+    op: impl Fn(&Sftp) -> Result<T, ssh2::Error>,
+) -> Result<T, String> {
+    let sftp = pooled_sftp(app, host_id)?;
+
+    match op(&sftp) {
+        Ok(v) => Ok(v),
+        Err(e) if matches!(e.code(), ErrorCode::Session(_)) => {
+            drop_sftp(host_id);
+            let sftp = pooled_sftp(app, host_id)?;
+            op(&sftp).map_err(sftp_error)
+        }
+        Err(e) => Err(sftp_error(e)),
+    }
+}
+
+pub fn sftp_error(e: ssh2::Error) -> String {
+    match e.code() {
+        ErrorCode::SFTP(FX_NO_SUCH_FILE) => "No such file or directory".into(),
+        ErrorCode::SFTP(FX_PERMISSION_DENIED) => "Permission denied".into(),
+        ErrorCode::SFTP(FX_FILE_ALREADY_EXISTS) => "File already exists".into(),
+        ErrorCode::SFTP(FX_DIR_NOT_EMPTY) => "Directory not empty".into(),
+        _ => e.to_string(),
+    }
+}
+
+/* =========================
+   MODELS
+========================= */
+
+#[derive(Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RemoteFileKind {
+    File,
+    Dir,
+    Symlink,
+    Other,
+}
+
+#[derive(Serialize)]
+pub struct RemoteFileEntry {
+    pub name: String,
+    pub path: String,
+    pub kind: RemoteFileKind,
+    pub size: Option<u64>,
+    pub permissions: Option<u32>,
+    pub mode: String,
+    pub uid: Option<u32>,
+    pub gid: Option<u32>,
+    pub mtime: Option<u64>,
+    pub atime: Option<u64>,
+}
+
+impl RemoteFileEntry {
+    pub fn from_stat(path: &Path, stat: &FileStat) -> Self {
+        let kind = file_kind(stat);
+
+        RemoteFileEntry {
+            name: path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.to_string_lossy().to_string()),
+            path: path.to_string_lossy().to_string(),
+            kind,
+            size: stat.size,
+            permissions: stat.perm.map(|p| p & 0o7777),
+            mode: stat.perm.map(format_mode).unwrap_or_default(),
+            uid: stat.uid,
+            gid: stat.gid,
+            mtime: stat.mtime,
+            atime: stat.atime,
+        }
+    }
+}
+
+pub fn file_kind(stat: &FileStat) -> RemoteFileKind {
+    let ft = stat.file_type();
+    if ft.is_symlink() {
+        RemoteFileKind::Symlink
+    } else if ft.is_dir() {
+        RemoteFileKind::Dir
+    } else if ft.is_file() {
+        RemoteFileKind::File
+    } else {
+        RemoteFileKind::Other
+    }
+}
+
+fn format_mode(perm: u32) -> String {
+    let kind = match perm & 0o170000 {
+        0o040000 => 'd',
+        0o120000 => 'l',
+        0o020000 => 'c',
+        0o060000 => 'b',
+        0o010000 => 'p',
+        0o140000 => 's',
+        _ => '-',
+    };
+
+    let mut out = String::with_capacity(10);
+    out.push(kind);
+    for shift in [6, 3, 0] {
+        let bits = (perm >> shift) & 0o7;
+        out.push(if bits & 0o4 != 0 { 'r' } else { '-' });
+        out.push(if bits & 0o2 != 0 { 'w' } else { '-' });
+        out.push(if bits & 0o1 != 0 { 'x' } else { '-' });
+    }
+    out
+}
+
+/* =========================
+   TAURI COMMANDS
+========================= */
+
+#[tauri::command]
+pub fn sftp_list_dir(
+    host_id: i64,
+    path: String,
+    app: AppHandle,
+) -> Result<Vec<RemoteFileEntry>, String> {
+    let dir = PathBuf::from(&path);
+    let entries = with_sftp(&app, host_id, |sftp| sftp.readdir(&dir))?;
+
+    let mut items: Vec<RemoteFileEntry> = entries
+        .iter()
+        .filter(|(p, _)| {
+            !matches!(
+                p.file_name().and_then(|n| n.to_str()),
+                Some(".") | Some("..")
+            )
+        })
+        .map(|(p, stat)| RemoteFileEntry::from_stat(p, stat))
+        .collect();
+
+    // folders first, then by name
+    items.sort_by(|a, b| {
+        (b.kind == RemoteFileKind::Dir)
+            .cmp(&(a.kind == RemoteFileKind::Dir))
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+
+    Ok(items)
+}
+
+#[tauri::command]
+pub fn sftp_stat(
+    host_id: i64,
+    path: String,
+    follow_symlinks: Option<bool>,
+    app: AppHandle,
+) -> Result<RemoteFileEntry, String> {
+    let p = PathBuf::from(&path);
+    let stat = with_sftp(&app, host_id, |sftp| {
+        if follow_symlinks.unwrap_or(true) {
+            sftp.stat(&p)
+        } else {
+            sftp.lstat(&p)
+        }
+    })?;
+
+    Ok(RemoteFileEntry::from_stat(&p, &stat))
+}
+
+#[tauri::command]
+pub fn sftp_realpath(host_id: i64, path: String, app: AppHandle) -> Result<String, String> {
+    let p = PathBuf::from(&path);
+    let real = with_sftp(&app, host_id, |sftp| sftp.realpath(&p))?;
+    Ok(real.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+pub fn sftp_mkdir(
+    host_id: i64,
+    path: String,
+    mode: Option<i32>,
+    app: AppHandle,
+) -> Result<(), String> {
+    let p = PathBuf::from(&path);
+    with_sftp(&app, host_id, |sftp| {
+        sftp.mkdir(&p, mode.unwrap_or(DEFAULT_DIR_MODE))
+    })
+}
+
+#[tauri::command]
+pub fn sftp_rmdir(host_id: i64, path: String, app: AppHandle) -> Result<(), String> {
+    let p = PathBuf::from(&path);
+    with_sftp(&app, host_id, |sftp| sftp.rmdir(&p))
+}
+
+#[tauri::command]
+pub fn sftp_rename(
+    host_id: i64,
+    from: String,
+    to: String,
+    overwrite: Option<bool>,
+    app: AppHandle,
+) -> Result<(), String> {
+    let src = PathBuf::from(&from);
+    let dst = PathBuf::from(&to);
+    let flags = if overwrite.unwrap_or(false) {
+        RenameFlags::OVERWRITE | RenameFlags::ATOMIC | RenameFlags::NATIVE
+    } else {
+        RenameFlags::ATOMIC | RenameFlags::NATIVE
+    };
+
+    with_sftp(&app, host_id, |sftp| sftp.rename(&src, &dst, Some(flags)))
+}
+
+#[tauri::command]
+pub fn sftp_unlink(host_id: i64, path: String, app: AppHandle) -> Result<(), String> {
+    let p = PathBuf::from(&path);
+    with_sftp(&app, host_id, |sftp| sftp.unlink(&p))
+}
+
+#[tauri::command]
+pub fn sftp_symlink(
+    host_id: i64,
+    target: String,
+    link_path: String,
+    app: AppHandle,
+) -> Result<(), String> {
+    let target = PathBuf::from(&target);
+    let link = PathBuf::from(&link_path);
+    with_sftp(&app, host_id, |sftp| sftp.symlink(&target, &link))
+}
+
+#[tauri::command]
+pub fn sftp_readlink(host_id: i64, path: String, app: AppHandle) -> Result<String, String> {
+    let p = PathBuf::from(&path);
+    let target = with_sftp(&app, host_id, |sftp| sftp.readlink(&p))?;
+    Ok(target.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+pub fn sftp_disconnect(host_id: i64) {
+    drop_sftp(host_id);
+}
@@ -0,0 +1,920 @@
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+use tauri::{AppHandle, Manager};
+
+use crate::db::Db;
+use crate::import::{apply_import, preview_import, DuplicateMode, ImportHost, ImportPreview, ImportResult};
+
+/* =========================
+   CONFIG
+========================= */
+
+const MAX_INCLUDE_DEPTH: usize = 16;
+const DEFAULT_PORT: i64 = 22;
+/// First line of every exported file; only files starting with it are
+/// ever overwritten or removed
+const EXPORT_MARKER: &str = "# Generated by NetHopper";
+
+/* =========================
+   TAURI COMMANDS
+========================= */
+
+/// Dry run of `import_ssh_config`. `path` defaults to `~/.ssh/config`.
+#[tauri::command]
+pub fn preview_ssh_config_import(
+    path: Option<String>,
+    group_id: Option<i64>,
+    app: AppHandle,
+) -> Result<ImportPreview, String> {
+    let (hosts, warnings) = load_ssh_config(&app, path)?;
+
+    let db = app.state::<Db>();
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    preview_import(&conn, hosts, group_id, warnings)
+}
+
+/// Hosts from the main file go into `group_id`; hosts from an included file
+/// go into a sub group named after that file.
+#[tauri::command]
+pub fn import_ssh_config(
+    path: Option<String>,
+    group_id: Option<i64>,
+    duplicates: Option<DuplicateMode>,
+    app: AppHandle,
+) -> Result<ImportResult, String> {
+    let (hosts, warnings) = load_ssh_config(&app, path)?;
+
+    let db = app.state::<Db>();
+    let mut conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    apply_import(&mut conn, hosts, group_id, duplicates.unwrap_or_default(), warnings)
+}
+
+fn load_ssh_config(
+    app: &AppHandle,
+    path: Option<String>,
+) -> Result<(Vec<ImportHost>, Vec<String>), String> {
+    let home = app.path().home_dir().map_err(|e| e.to_string())?;
+    let path = match path {
+        Some(p) => expand_tilde(&p, &home),
+        None => home.join(".ssh").join("config"),
+    };
+
+    let mut parser = Parser {
+        home,
+        blocks: vec![Block::global()],
+        warnings: Vec::new(),
+    };
+    parser.parse_file(&path, None, 0)?;
+
+    Ok(parser.resolve())
+}
+
+/// Renders the export in the single-file layout without writing anything
+#[tauri::command]
+pub fn render_ssh_config(group_id: Option<i64>, db: tauri::State<Db>) -> Result<String, String> {
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    let sections = load_sections(&conn, group_id)?;
+    let aliases = assign_aliases(&sections);
+
+    let mut out = export_header();
+    for section in &sections {
+        write_section(&mut out, section, &aliases, true);
+    }
+    Ok(out)
+}
+
+/// Writes the inventory (or one group subtree) to `path`.
+/// With `ExportLayout::Include` every group gets its own file in `<path>.d/`,
+/// which is owned by the export: stale `.conf` files there are removed.
+#[tauri::command]
+pub fn export_ssh_config(
+    path: String,
+    group_id: Option<i64>,
+    layout: Option<ExportLayout>,
+    app: AppHandle,
+) -> Result<ExportResult, String> {
+    let home = app.path().home_dir().map_err(|e| e.to_string())?;
+    let path = expand_tilde(&path, &home);
+
+    let sections = {
+        let db = app.state::<Db>();
+        let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+        load_sections(&conn, group_id)?
+    };
+    let aliases = assign_aliases(&sections);
+
+    let mut result = ExportResult {
+        files: Vec::new(),
+        hosts: aliases.by_id.len(),
+    };
+    let mut main = export_header();
+
+    match layout.unwrap_or_default() {
+        ExportLayout::Comments => {
+            for section in &sections {
+                write_section(&mut main, section, &aliases, true);
+            }
+        }
+        ExportLayout::Include => {
+            let dir = include_dir(&path);
+            fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+            remove_stale_includes(&dir)?;
+            main.push_str(&write_includes(&dir, &sections, &aliases, &mut result.files)?);
+        }
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::write(&path, main).map_err(|e| e.to_string())?;
+    result.files.insert(0, path.to_string_lossy().to_string());
+
+    Ok(result)
+}
+
+/* =========================
+   EXPORT
+========================= */
+
+#[derive(Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportLayout {
+    /// One file, groups become comment headers
+    #[default]
+    Comments,
+    /// One file per group, pulled in with Include from the main file
+    Include,
+}
+
+#[derive(Serialize)]
+pub struct ExportResult {
+    /// Written files, main config first
+    pub files: Vec<String>,
+    pub hosts: usize,
+}
+
+struct ExportHost {
+    id: i64,
+    name: String,
+    host: String,
+    port: i64,
+    username: String,
+    identity_file: Option<String>,
+    proxy_jump: Option<String>,
+}
+
+/// Hosts of a single group; `path` is empty for hosts without a group
+struct Section {
+    path: Vec<String>,
+    hosts: Vec<ExportHost>,
+}
+
+/// Depth-first walk of the group tree below `root`, in explorer order
+fn load_sections(conn: &Connection, root: Option<i64>) -> Result<Vec<Section>, String> {
+    let mut children: HashMap<Option<i64>, Vec<(i64, String)>> = HashMap::new();
+    {
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, name, parent_id FROM groups WHERE deleted_at IS NULL
+                 ORDER BY sort_order, name, id",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([], |r| Ok((r.get::<_, i64>(0)?, r.get::<_, String>(1)?, r.get(2)?)))
+            .map_err(|e| e.to_string())?;
+        for (id, name, parent) in rows.filter_map(Result::ok) {
+            children.entry(parent).or_default().push((id, name));
+        }
+    }
+
+    let mut sections = Vec::new();
+    let mut stack: Vec<(Option<i64>, Vec<String>)> = match root {
+        Some(id) => {
+            let name: String = conn
+                .query_row(
+                    "SELECT name FROM groups WHERE id = ? AND deleted_at IS NULL",
+                    [id],
+                    |r| r.get(0),
+                )
+                .map_err(|_| "Group not found")?;
+            vec![(Some(id), vec![name])]
+        }
+        None => vec![(None, Vec::new())],
+    };
+
+    while let Some((group_id, path)) = stack.pop() {
+        if let Some(subs) = children.get(&group_id) {
+            for (id, name) in subs.iter().rev() {
+                let mut sub_path = path.clone();
+                sub_path.push(name.clone());
+                stack.push((Some(*id), sub_path));
+            }
+        }
+
+        let hosts = load_group_hosts(conn, group_id)?;
+        if !hosts.is_empty() {
+            sections.push(Section { path, hosts });
+        }
+    }
+
+    Ok(sections)
+}
+
+fn load_group_hosts(conn: &Connection, group_id: Option<i64>) -> Result<Vec<ExportHost>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name, host, port, username, identity_file, proxy_jump
+             FROM hosts WHERE group_id IS ? AND deleted_at IS NULL ORDER BY sort_order, name, id",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map([group_id], |r| {
+            Ok(ExportHost {
+                id: r.get(0)?,
+                name: r.get(1)?,
+                host: r.get(2)?,
+                port: r.get(3)?,
+                username: r.get(4)?,
+                identity_file: r.get(5)?,
+                proxy_jump: r.get(6)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.filter_map(Result::ok).collect())
+}
+
+/// Unique `Host` alias per host. Names that are not valid patterns are
+/// slugged, clashes get the host id appended.
+struct Aliases {
+    by_id: HashMap<i64, String>,
+    /// Inventory name -> alias, so a ProxyJump stored by name follows renames
+    by_name: HashMap<String, String>,
+}
+
+fn assign_aliases(sections: &[Section]) -> Aliases {
+    let mut used = HashSet::new();
+    let mut aliases = Aliases {
+        by_id: HashMap::new(),
+        by_name: HashMap::new(),
+    };
+
+    for host in sections.iter().flat_map(|s| &s.hosts) {
+        let mut alias = slug(&host.name);
+        if alias.is_empty() {
+            alias = slug(&host.host);
+        }
+        if !used.insert(alias.clone()) {
+            alias = format!("{}-{}", alias, host.id);
+            used.insert(alias.clone());
+        }
+        aliases
+            .by_name
+            .entry(host.name.clone())
+            .or_insert_with(|| alias.clone());
+        aliases.by_id.insert(host.id, alias);
+    }
+
+    aliases
+}
+
+fn write_section(out: &mut String, section: &Section, aliases: &Aliases, heading: bool) {
+    if heading && !section.path.is_empty() {
+        out.push_str(&format!("\n# ===== {} =====\n", section.path.join(" / ")));
+    }
+
+    for host in &section.hosts {
+        out.push_str(&format!("\nHost {}\n", aliases.by_id[&host.id]));
+        out.push_str(&format!("    HostName {}\n", quote(&host.host)));
+        out.push_str(&format!("    Port {}\n", host.port));
+        out.push_str(&format!("    User {}\n", quote(&host.username)));
+        if let Some(identity) = host.identity_file.as_deref().filter(|v| !v.is_empty()) {
+            out.push_str(&format!("    IdentityFile {}\n", quote(identity)));
+        }
+        if let Some(jump) = host.proxy_jump.as_deref().filter(|v| !v.is_empty()) {
+            let jump = aliases.by_name.get(jump).map_or(jump, String::as_str);
+            out.push_str(&format!("    ProxyJump {}\n", jump));
+        }
+    }
+}
+
+fn export_header() -> String {
+    format!(
+        "{} on {}\n# Changes made here are overwritten by the next export\n",
+        EXPORT_MARKER,
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+    )
+}
+
+/// `~/.ssh/config` -> `~/.ssh/config.d`
+fn include_dir(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".d");
+    path.with_file_name(name)
+}
+
+/// Writes one file per group into `dir` and returns the main file body:
+/// Include lines first, they only apply inside a Host block after the
+/// first Host line, then the hosts without a group
+fn write_includes(
+    dir: &Path,
+    sections: &[Section],
+    aliases: &Aliases,
+    files: &mut Vec<String>,
+) -> Result<String, String> {
+    let mut includes = String::new();
+    let mut ungrouped = String::new();
+    let mut used = HashSet::new();
+
+    for section in sections {
+        if section.path.is_empty() {
+            write_section(&mut ungrouped, section, aliases, false);
+            continue;
+        }
+
+        let file = dir.join(include_file_name(&section.path, &mut used));
+        if file.exists() && !is_generated(&file) {
+            return Err(format!("{} was not written by NetHopper, not overwriting it", file.display()));
+        }
+        let mut body = export_header();
+        write_section(&mut body, section, aliases, true);
+        fs::write(&file, body).map_err(|e| e.to_string())?;
+
+        includes.push_str(&format!("Include {}\n", quote(&file.to_string_lossy())));
+        files.push(file.to_string_lossy().to_string());
+    }
+
+    includes.push_str(&ungrouped);
+    Ok(includes)
+}
+
+/// Group paths that slug the same (`Prod/DB`, `prod-db`) get a numeric
+/// suffix, so one group never overwrites another
+fn include_file_name(path: &[String], used: &mut HashSet<String>) -> String {
+    let mut base = slug(&path.join("-"));
+    if base.is_empty() {
+        base = "group".into();
+    }
+
+    let name = (1..)
+        .map(|n| if n == 1 { base.clone() } else { format!("{}-{}", base, n) })
+        .find(|name| !used.contains(name))
+        .unwrap();
+    used.insert(name.clone());
+    format!("{}.conf", name)
+}
+
+/// Removes files of a previous export, so renamed or deleted groups do not
+/// leave their hosts behind. Files the user put there are kept.
+fn remove_stale_includes(dir: &Path) -> Result<(), String> {
+    for entry in fs::read_dir(dir).map_err(|e| e.to_string())?.flatten() {
+        let path = entry.path();
+        if path.extension().is_some_and(|e| e == "conf") && is_generated(&path) {
+            fs::remove_file(&path).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+fn is_generated(path: &Path) -> bool {
+    fs::read_to_string(path).is_ok_and(|text| text.starts_with(EXPORT_MARKER))
+}
+
+/* =========================
+   PARSER
+========================= */
+
+struct Block {
+    patterns: Vec<String>,
+    /// Stem of the included file the block came from, `None` for the main file
+    source: Option<String>,
+    options: Vec<(String, String)>,
+}
+
+impl Block {
+    fn global() -> Self {
+        Block {
+            patterns: vec!["*".into()],
+            source: None,
+            options: Vec::new(),
+        }
+    }
+
+    fn matches(&self, alias: &str) -> bool {
+        let mut matched = false;
+        for p in &self.patterns {
+            if let Some(neg) = p.strip_prefix('!') {
+                if wildcard_match(neg, alias) {
+                    return false;
+                }
+            } else if wildcard_match(p, alias) {
+                matched = true;
+            }
+        }
+        matched
+    }
+}
+
+struct Parser {
+    home: PathBuf,
+    blocks: Vec<Block>,
+    warnings: Vec<String>,
+}
+
+impl Parser {
+    fn parse_file(&mut self, path: &Path, source: Option<String>, depth: usize) -> Result<(), String> {
+        if depth > MAX_INCLUDE_DEPTH {
+            return Err(format!("Include nesting too deep at {}", path.display()));
+        }
+
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        self.parse_text(&text, path, source, depth)
+    }
+
+    /// `path` only labels warnings and is not read
+    fn parse_text(&mut self, text: &str, path: &Path, source: Option<String>, depth: usize) -> Result<(), String> {
+        for (lineno, raw) in text.lines().enumerate() {
+            let line = raw.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (keyword, args) = split_keyword(line);
+            let keyword = keyword.to_lowercase();
+            let args = split_args(args);
+
+            match keyword.as_str() {
+                "host" => self.blocks.push(Block {
+                    patterns: args,
+                    source: source.clone(),
+                    options: Vec::new(),
+                }),
+                "match" => {
+                    self.warnings.push(format!(
+                        "{}:{}: Match blocks are not supported, skipped",
+                        path.display(),
+                        lineno + 1
+                    ));
+                    // options up to the next Host line belong to the Match block
+                    self.blocks.push(Block {
+                        patterns: Vec::new(),
+                        source: source.clone(),
+                        options: Vec::new(),
+                    });
+                }
+                "include" => {
+                    for pattern in args {
+                        self.include(&pattern, depth)?;
+                    }
+                }
+                _ => {
+                    if let Some(value) = args.into_iter().next() {
+                        self.blocks
+                            .last_mut()
+                            .unwrap()
+                            .options
+                            .push((keyword, value));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn include(&mut self, pattern: &str, depth: usize) -> Result<(), String> {
+        let expanded = expand_tilde(pattern, &self.home);
+        // relative includes are resolved against ~/.ssh like OpenSSH does for user configs
+        let full = if expanded.is_absolute() {
+            expanded
+        } else {
+            self.home.join(".ssh").join(expanded)
+        };
+
+        let matches = glob::glob(&full.to_string_lossy())
+            .map_err(|e| format!("Invalid Include '{}': {}", pattern, e))?;
+
+        // Host blocks inside the include end at its last line
+        let resume = self.blocks.last().map(|b| (b.patterns.clone(), b.source.clone()));
+
+        for entry in matches.flatten() {
+            if entry.is_file() {
+                let stem = entry
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().to_string());
+                self.parse_file(&entry, stem, depth + 1)?;
+            }
+        }
+
+        if let Some((patterns, source)) = resume {
+            self.blocks.push(Block {
+                patterns,
+                source,
+                options: Vec::new(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Applies OpenSSH precedence (first value wins, in file order) to every concrete alias
+    fn resolve(self) -> (Vec<ImportHost>, Vec<String>) {
+        let mut hosts = Vec::new();
+        let mut seen = Vec::new();
+        let mut warnings = self.warnings;
+        let default_user = std::env::var("USER")
+            .or_else(|_| std::env::var("USERNAME"))
+            .unwrap_or_default();
+
+        for block in &self.blocks {
+            for alias in &block.patterns {
+                if is_wildcard(alias) || seen.contains(alias) {
+                    continue;
+                }
+                seen.push(alias.clone());
+
+                let mut opts: HashMap<&str, &str> = HashMap::new();
+                for b in self.blocks.iter().filter(|b| b.matches(alias)) {
+                    for (k, v) in &b.options {
+                        opts.entry(k.as_str()).or_insert(v.as_str());
+                    }
+                }
+
+                let hostname = opts
+                    .get("hostname")
+                    .map(|h| h.replace("%h", alias))
+                    .unwrap_or_else(|| alias.clone());
+
+                let port = match opts.get("port").map(|p| p.parse::<i64>()) {
+                    Some(Ok(p)) => p,
+                    Some(Err(_)) => {
+                        warnings.push(format!("{}: invalid Port, using {}", alias, DEFAULT_PORT));
+                        DEFAULT_PORT
+                    }
+                    None => DEFAULT_PORT,
+                };
+
+                let identity_file = opts
+                    .get("identityfile")
+                    .filter(|v| !v.eq_ignore_ascii_case("none"))
+                    .map(|v| expand_tilde(v, &self.home).to_string_lossy().to_string());
+
+                let proxy_jump = opts
+                    .get("proxyjump")
+                    .filter(|v| !v.eq_ignore_ascii_case("none"))
+                    .map(|v| v.to_string());
+
+                hosts.push(ImportHost {
+                    name: alias.clone(),
+                    host: hostname,
+                    port,
+                    username: opts
+                        .get("user")
+                        .map(|u| u.to_string())
+                        .unwrap_or_else(|| default_user.clone()),
+                    password: None,
+                    auth_type: if identity_file.is_some() { "key" } else { "password" }.into(),
+                    identity_file,
+                    proxy_jump,
+                    group_path: block.source.iter().cloned().collect(),
+                });
+            }
+        }
+
+        (hosts, warnings)
+    }
+}
+
+/* =========================
+   HELPERS
+========================= */
+
+/// `Keyword value` or `Keyword=value`
+fn split_keyword(line: &str) -> (&str, &str) {
+    let end = line
+        .find(|c: char| c.is_whitespace() || c == '=')
+        .unwrap_or(line.len());
+    let rest = line[end..].trim_start();
+    let rest = rest.strip_prefix('=').unwrap_or(rest).trim_start();
+    (&line[..end], rest)
+}
+
+/// Whitespace separated arguments, double quotes group words
+fn split_args(s: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut cur = String::new();
+    let mut quoted = false;
+
+    for c in s.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !cur.is_empty() {
+                    args.push(std::mem::take(&mut cur));
+                }
+            }
+            c => cur.push(c),
+        }
+    }
+    if !cur.is_empty() {
+        args.push(cur);
+    }
+    args
+}
+
+fn is_wildcard(pattern: &str) -> bool {
+    pattern.contains('*') || pattern.contains('?') || pattern.starts_with('!')
+}
+
+/// OpenSSH host pattern: `*` any run of characters, `?` exactly one
+pub fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let p: Vec<char> = pattern.to_lowercase().chars().collect();
+    let t: Vec<char> = text.to_lowercase().chars().collect();
+
+    let (mut pi, mut ti) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while ti < t.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == t[ti]) {
+            pi += 1;
+            ti += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            star = Some((pi, ti));
+            pi += 1;
+        } else if let Some((sp, st)) = star {
+            pi = sp + 1;
+            ti = st + 1;
+            star = Some((sp, st + 1));
+        } else {
+            return false;
+        }
+    }
+
+    p[pi..].iter().all(|c| *c == '*')
+}
+
+/// Lowercase alias safe for `Host` lines and file names
+fn slug(name: &str) -> String {
+    let mut out = String::new();
+    for c in name.trim().chars() {
+        if c.is_alphanumeric() || matches!(c, '.' | '_' | '-' | '@') {
+            out.push(c);
+        } else if !out.ends_with('-') {
+            out.push('-');
+        }
+    }
+    // OpenSSH matches aliases case-insensitively
+    out.trim_matches('-').to_lowercase()
+}
+
+fn quote(value: &str) -> String {
+    if value.contains(char::is_whitespace) {
+        format!("\"{}\"", value)
+    } else {
+        value.to_string()
+    }
+}
+
+fn expand_tilde(path: &str, home: &Path) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => home.join(rest),
+        None if path == "~" => home.to_path_buf(),
+        None => PathBuf::from(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parser(home: &Path) -> Parser {
+        Parser {
+            home: home.to_path_buf(),
+            blocks: vec![Block::global()],
+            warnings: Vec::new(),
+        }
+    }
+
+    fn parse(text: &str) -> (Vec<ImportHost>, Vec<String>) {
+        let mut p = parser(Path::new("/home/test"));
+        p.parse_text(text, Path::new("config"), None, 0).unwrap();
+        p.resolve()
+    }
+
+    fn host<'a>(hosts: &'a [ImportHost], name: &str) -> &'a ImportHost {
+        hosts.iter().find(|h| h.name == name).unwrap()
+    }
+
+    /// Fresh directory under the system temp dir, removed by the caller
+    fn temp_home(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("nethopper-ssh-config-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join(".ssh").join("config.d")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn wildcard_star_and_question_mark() {
+        assert!(wildcard_match("*", "anything"));
+        assert!(wildcard_match("web-*", "web-01"));
+        assert!(wildcard_match("web-*", "web-"));
+        assert!(!wildcard_match("web-*", "db-01"));
+        assert!(wildcard_match("*.example.com", "a.b.example.com"));
+        assert!(wildcard_match("db?", "db1"));
+        assert!(!wildcard_match("db?", "db"));
+        assert!(!wildcard_match("db?", "db12"));
+        assert!(wildcard_match("d*b?x", "dzzzb1x"));
+        assert!(wildcard_match("WEB*", "web1"));
+    }
+
+    #[test]
+    fn splits_keyword_and_value() {
+        assert_eq!(split_keyword("HostName example.com"), ("HostName", "example.com"));
+        assert_eq!(split_keyword("Port=2222"), ("Port", "2222"));
+        assert_eq!(split_keyword("Port = 2222"), ("Port", "2222"));
+        assert_eq!(split_keyword("User\t admin"), ("User", "admin"));
+        assert_eq!(split_args(r#"a "b c" d"#), vec!["a", "b c", "d"]);
+    }
+
+    #[test]
+    fn first_value_wins_across_blocks() {
+        let (hosts, warnings) = parse(
+            "# comment\n\
+             Host web\n\
+             HostName=web.example.com\n\
+             Port 2200\n\
+             Host *\n\
+             User deploy\n\
+             Port 22\n",
+        );
+        assert!(warnings.is_empty());
+        let web = host(&hosts, "web");
+        assert_eq!(web.host, "web.example.com");
+        assert_eq!(web.port, 2200);
+        assert_eq!(web.username, "deploy");
+        assert_eq!(web.auth_type, "password");
+    }
+
+    #[test]
+    fn negated_patterns_exclude_hosts() {
+        let (hosts, _) = parse(
+            "Host web db\n\
+             User admin\n\
+             Host * !db\n\
+             IdentityFile ~/.ssh/id_web\n",
+        );
+        let web = host(&hosts, "web");
+        assert_eq!(web.identity_file.as_deref(), Some("/home/test/.ssh/id_web"));
+        assert_eq!(web.auth_type, "key");
+        assert!(host(&hosts, "db").identity_file.is_none());
+        // patterns are never imported as hosts
+        assert_eq!(hosts.len(), 2);
+    }
+
+    #[test]
+    fn hostname_token_and_invalid_port() {
+        let (hosts, warnings) = parse(
+            "Host box\n\
+             HostName %h.internal\n\
+             Port nope\n\
+             User root\n",
+        );
+        let b = host(&hosts, "box");
+        assert_eq!(b.host, "box.internal");
+        assert_eq!(b.port, DEFAULT_PORT);
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn match_blocks_are_skipped_with_warning() {
+        let (hosts, warnings) = parse(
+            "Match host foo\n\
+             User ignored\n\
+             Host foo\n\
+             User real\n",
+        );
+        assert_eq!(host(&hosts, "foo").username, "real");
+        assert!(warnings[0].contains("Match"));
+    }
+
+    #[test]
+    fn slug_is_lowercase_and_safe() {
+        assert_eq!(slug("Web Server"), "web-server");
+        assert_eq!(slug("  Prod / DB #1 "), "prod-db-1");
+        assert_eq!(slug("deploy@Box_01.lan"), "deploy@box_01.lan");
+        assert_eq!(slug("***"), "");
+    }
+
+    #[test]
+    fn colliding_group_paths_get_their_own_include() {
+        let section = |path: &[&str], id: i64| Section {
+            path: path.iter().map(|p| p.to_string()).collect(),
+            hosts: vec![ExportHost {
+                id,
+                name: format!("host{}", id),
+                host: format!("10.0.0.{}", id),
+                port: 22,
+                username: "ops".into(),
+                identity_file: None,
+                proxy_jump: None,
+            }],
+        };
+        let sections = vec![
+            section(&["Prod", "DB"], 1),
+            section(&["Prod DB"], 2),
+            section(&["prod-db"], 3),
+            section(&["***"], 4),
+            section(&[], 5),
+        ];
+        let aliases = assign_aliases(&sections);
+
+        let home = temp_home("collide");
+        let dir = home.join(".ssh").join("config.d");
+        let mut files = Vec::new();
+        let main = write_includes(&dir, &sections, &aliases, &mut files).unwrap();
+        let names: Vec<String> = files
+            .iter()
+            .map(|f| Path::new(f).file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        let bodies: Vec<String> = files.iter().map(|f| fs::read_to_string(f).unwrap()).collect();
+        fs::remove_dir_all(&home).unwrap();
+
+        assert_eq!(names, ["prod-db.conf", "prod-db-2.conf", "prod-db-3.conf", "group.conf"]);
+        for (i, body) in bodies.iter().enumerate() {
+            assert!(body.contains(&format!("Host host{}\n", i + 1)), "{}", body);
+        }
+        assert_eq!(main.matches("Include ").count(), 4);
+        assert!(main.find("Include ").unwrap() < main.find("Host host5").unwrap());
+    }
+
+    #[test]
+    fn stale_includes_keep_user_files() {
+        let home = temp_home("stale");
+        let dir = home.join(".ssh").join("config.d");
+        fs::write(dir.join("old.conf"), export_header()).unwrap();
+        fs::write(dir.join("mine.conf"), "Host mine\n").unwrap();
+        fs::write(dir.join("notes.txt"), export_header()).unwrap();
+
+        remove_stale_includes(&dir).unwrap();
+        let old_exists = dir.join("old.conf").exists();
+        let mine_exists = dir.join("mine.conf").exists();
+        let notes_exists = dir.join("notes.txt").exists();
+        fs::remove_dir_all(&home).unwrap();
+
+        assert!(!old_exists);
+        assert!(mine_exists);
+        assert!(notes_exists);
+    }
+
+    #[test]
+    fn includes_go_into_named_groups() {
+        let home = temp_home("include");
+        fs::write(
+            home.join(".ssh").join("config.d").join("work.conf"),
+            "Host build\n    HostName build.work\n    User ci\n",
+        )
+        .unwrap();
+
+        let mut p = parser(&home);
+        p.parse_text(
+            "Host main\n\
+             User me\n\
+             Include config.d/*.conf\n\
+             Port 2022\n",
+            Path::new("config"),
+            None,
+            0,
+        )
+        .unwrap();
+        let (hosts, warnings) = p.resolve();
+        fs::remove_dir_all(&home).unwrap();
+
+        assert!(warnings.is_empty());
+        let build = host(&hosts, "build");
+        assert_eq!(build.host, "build.work");
+        assert_eq!(build.group_path, vec!["work".to_string()]);
+        // the Host block that was open before the Include continues after it
+        let main = host(&hosts, "main");
+        assert_eq!(main.port, 2022);
+        assert!(main.group_path.is_empty());
+    }
+
+    #[test]
+    fn include_loops_are_cut_off() {
+        let home = temp_home("loop");
+        let path = home.join(".ssh").join("config");
+        fs::write(&path, "Include ~/.ssh/config\n").unwrap();
+
+        let mut p = parser(&home);
+        let result = p.parse_file(&path, None, 0);
+        fs::remove_dir_all(&home).unwrap();
+
+        assert!(result.unwrap_err().contains("too deep"));
+    }
+}
@@ -0,0 +1,85 @@
+use ssh2::Session;
+use std::{
+    net::{TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+use tauri::{AppHandle, Manager};
+
+use crate::db::Db;
+
+/* =========================
+   CONFIG
+========================= */
+
+const SSH_TIMEOUT_SECS: u64 = 10;
+/// Timeout for operations on an established session (SFTP, forwarding setup)
+const SSH_OP_TIMEOUT_MS: u32 = 30_000;
+
+/* =========================
+   HOST
+========================= */
+
+pub struct HostTarget {
+    pub host: String,
+    pub port: i64,
+    pub username: String,
+    pub password: Option<String>,
+}
+
+pub fn load_host(app: &AppHandle, host_id: i64) -> Result<HostTarget, String> {
+    let db = app.state::<Db>();
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+
+    conn.query_row(
+        "SELECT host, port, username, password FROM hosts WHERE id = ? AND deleted_at IS NULL",
+        [host_id],
+        |r| {
+            Ok(HostTarget {
+                host: r.get(0)?,
+                port: r.get(1)?,
+                username: r.get(2)?,
+                password: r.get(3)?,
+            })
+        },
+    )
+    .map_err(|e| e.to_string())
+}
+
+/* =========================
+   CONNECT
+========================= */
+
+/// Opens a new authenticated session to the host. Each caller owns its session;
+/// pooling is up to the caller.
+pub fn connect_host(app: &AppHandle, host_id: i64) -> Result<Session, String> {
+    let target = load_host(app, host_id)?;
+
+    let password = target
+        .password
+        .ok_or_else(|| "Password auth required".to_string())?;
+
+    let addr = (target.host.as_str(), target.port as u16)
+        .to_socket_addrs()
+        .map_err(|_| "Invalid address")?
+        .next()
+        .ok_or("Resolve failed")?;
+
+    let tcp = TcpStream::connect_timeout(&addr, Duration::from_secs(SSH_TIMEOUT_SECS))
+        .map_err(|_| "SSH connect timeout".to_string())?;
+
+    let mut sess = Session::new().map_err(|_| "SSH session failed".to_string())?;
+    sess.set_tcp_stream(tcp);
+    sess.set_timeout(SSH_TIMEOUT_SECS as u32 * 1000);
+
+    sess.handshake().map_err(|e| format!("Handshake failed: {}", e))?;
+
+    sess.userauth_password(&target.username, &password)
+        .map_err(|_| "SSH authentication failed".to_string())?;
+
+    if !sess.authenticated() {
+        return Err("SSH authentication failed".into());
+    }
+
+    sess.set_timeout(SSH_OP_TIMEOUT_MS);
+    Ok(sess)
+}
@@ -9,11 +9,14 @@ use std::{
         atomic::{AtomicBool, Ordering},
         Arc, Mutex,
     },
-    time::Duration,
+    time::{Duration, Instant},
 };
 use tauri::{AppHandle, Emitter, Manager};
 
 use crate::db::Db;
+use crate::history::{record_session_end, record_session_start, FailureKind, SessionOutcome};
+use crate::session_log::{LogHost, SessionLogger};
+use crate::tunnel_profile::autostart_for_host;
 
 /* =========================
    CONFIG
@@ -136,11 +139,11 @@ fn ssh_exec_worker(
     let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
     println!("[SSH Worker] DB lock acquired");
 
-    let (host, port, username, password): (String, i64, String, Option<String>) = conn
+    let (name, host, port, username, password): (String, String, i64, String, Option<String>) = conn
         .query_row(
-            "SELECT host, port, username, password FROM hosts WHERE id = ?",
+            "SELECT name, host, port, username, password FROM hosts WHERE id = ? AND deleted_at IS NULL",
             [host_id],
-            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)),
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?, r.get(4)?)),
         )
         .map_err(|e| {
             println!("[SSH Worker] DB query failed: {:?}", e);
@@ -150,23 +153,74 @@ fn ssh_exec_worker(
     println!("[SSH Worker] Host data loaded: {}:{}", host, port);
     drop(conn);
 
-    let password = password.ok_or_else(|| {
+    // nothing was attempted yet, so this is not a session for the history
+    let Some(password) = password else {
         println!("[SSH Worker] No password found");
-        "Password auth required".to_string()
-    })?;
+        return Err("Password auth required".into());
+    };
+    let target = ShellTarget {
+        name,
+        host,
+        port,
+        username,
+        password,
+    };
+
+    /* ===== HISTORY ===== */
+    let history_id = record_session_start(&app, host_id);
+    let started = Instant::now();
+
+    let outcome = match run_shell(&task_id, host_id, &task, &app, stdin_rx, target) {
+        Ok(exit_code) if task.cancel.load(Ordering::Relaxed) => SessionOutcome::Cancelled { exit_code },
+        Ok(exit_code) => SessionOutcome::Closed { exit_code },
+        Err((kind, error)) => SessionOutcome::Failed { kind, error },
+    };
+    record_session_end(&app, history_id, started.elapsed(), &outcome);
+
+    match outcome {
+        SessionOutcome::Failed { error, .. } => Err(error),
+        _ => Ok(()),
+    }
+}
+
+struct ShellTarget {
+    name: String,
+    host: String,
+    port: i64,
+    username: String,
+    password: String,
+}
+
+/// Connects, runs the interactive shell until it closes and returns its exit
+/// code; failures carry the stage they happened in for the connection history
+fn run_shell(
+    task_id: &str,
+    host_id: i64,
+    task: &SshTask,
+    app: &AppHandle,
+    stdin_rx: std::sync::mpsc::Receiver<Vec<u8>>,
+    target: ShellTarget,
+) -> Result<i32, (FailureKind, String)> {
+    let ShellTarget {
+        name,
+        host,
+        port,
+        username,
+        password,
+    } = target;
 
     /* ===== TCP ===== */
     println!("[SSH Worker] Connecting to {}:{}...", host, port);
     let addr = (host.as_str(), port as u16)
         .to_socket_addrs()
-        .map_err(|_| "Invalid address")?
+        .map_err(|_| (FailureKind::Resolve, "Invalid address".to_string()))?
         .next()
-        .ok_or("Resolve failed")?;
+        .ok_or((FailureKind::Resolve, "Resolve failed".to_string()))?;
 
     let tcp =
         TcpStream::connect_timeout(&addr, Duration::from_secs(SSH_TIMEOUT_SECS)).map_err(|e| {
             println!("[SSH Worker] TCP connect failed: {:?}", e);
-            "SSH connect timeout".to_string()
+            (FailureKind::Connect, "SSH connect timeout".to_string())
         })?;
 
     println!("[SSH Worker] TCP connected");
@@ -175,7 +229,7 @@ fn ssh_exec_worker(
     println!("[SSH Worker] Starting SSH handshake...");
     let mut sess = Session::new().map_err(|e| {
         println!("[SSH Worker] Session creation failed: {:?}", e);
-        "SSH session failed".to_string()
+        (FailureKind::Handshake, "SSH session failed".to_string())
     })?;
     sess.set_tcp_stream(tcp);
 
@@ -184,21 +238,23 @@ fn ssh_exec_worker(
 
     sess.handshake().map_err(|e| {
         println!("[SSH Worker] Handshake failed: {:?}", e);
-        e.to_string()
+        (FailureKind::Handshake, e.to_string())
     })?;
     println!("[SSH Worker] SSH handshake completed");
 
     sess.userauth_password(&username, &password).map_err(|e| {
         println!("[SSH Worker] Auth failed: {:?}", e);
-        e.to_string()
+        (FailureKind::Auth, e.to_string())
     })?;
     println!("[SSH Worker] Authentication successful");
 
+    autostart_for_host(app, host_id);
+
     /* ===== CHANNEL ===== */
     println!("[SSH Worker] Opening SSH channel...");
     let mut channel = sess.channel_session().map_err(|e| {
         println!("[SSH Worker] Channel creation failed: {:?}", e);
-        e.to_string()
+        (FailureKind::Channel, e.to_string())
     })?;
     println!("[SSH Worker] Channel created");
 
@@ -206,16 +262,38 @@ fn ssh_exec_worker(
         .request_pty("xterm-256color", None, None)
         .map_err(|e| {
             println!("[SSH Worker] PTY request failed: {:?}", e);
-            e.to_string()
+            (FailureKind::Channel, e.to_string())
         })?;
     println!("[SSH Worker] PTY requested");
 
     channel.shell().map_err(|e| {
         println!("[SSH Worker] Shell request failed: {:?}", e);
-        e.to_string()
+        (FailureKind::Channel, e.to_string())
     })?;
     println!("[SSH Worker] Shell started");
 
+    /* ===== SESSION LOG ===== */
+    let mut logger = match SessionLogger::open(
+        app,
+        &LogHost {
+            id: host_id,
+            name,
+            address: host.clone(),
+            port,
+            username: username.clone(),
+        },
+    ) {
+        Ok(logger) => logger,
+        Err(e) => {
+            // Logging must never block the terminal itself
+            println!("[SSH Worker] Session log disabled: {}", e);
+            None
+        }
+    };
+    if let Some(l) = &logger {
+        println!("[SSH Worker] Logging session to {}", l.path().display());
+    }
+
     // Set reasonable timeout for I/O operations
     // 200ms is enough to detect real errors but not cause unnecessary delays
     sess.set_timeout(100);
@@ -226,16 +304,16 @@ fn ssh_exec_worker(
     std::thread::sleep(Duration::from_millis(300));
 
     // Create channel for output thread to signal errors
-    let (output_err_tx, output_err_rx) = std::sync::mpsc::channel::<String>();
-    let (input_err_tx, input_err_rx) = std::sync::mpsc::channel::<String>();
+    let (output_err_tx, output_err_rx) = std::sync::mpsc::channel::<(FailureKind, String)>();
+    let (input_err_tx, input_err_rx) = std::sync::mpsc::channel::<(FailureKind, String)>();
 
     // Wrap channel in Arc<Mutex> for sharing between threads
     let channel = Arc::new(Mutex::new(channel));
     let channel_reader = channel.clone();
     let channel_writer = channel.clone();
 
-    let task_id_reader = task_id.clone();
-    let task_id_writer = task_id.clone();
+    let task_id_reader = task_id.to_string();
+    let task_id_writer = task_id.to_string();
     let app_reader = app.clone();
     let cancel_reader = task.cancel.clone();
     let cancel_writer = task.cancel.clone();
@@ -255,7 +333,7 @@ fn ssh_exec_worker(
                 Ok(ch) => ch,
                 Err(e) => {
                     println!("[Reader] Lock failed: {:?}", e);
-                    let _ = output_err_tx.send("Lock failed".into());
+                    let _ = output_err_tx.send((FailureKind::Io, "Lock failed".into()));
                     break;
                 }
             };
@@ -268,9 +346,15 @@ fn ssh_exec_worker(
                 Ok(n) => {
                     total += n;
                     if total > MAX_OUTPUT_BYTES {
-                        let _ = output_err_tx.send("Output limit exceeded".into());
+                        let _ = output_err_tx.send((FailureKind::OutputLimit, "Output limit exceeded".into()));
                         break;
                     }
+                    if let Some(l) = logger.as_mut() {
+                        if let Err(e) = l.write(&buf[..n]) {
+                            println!("[Reader] Session log write failed: {}", e);
+                            logger = None;
+                        }
+                    }
                     emit_stdout(
                         &app_reader,
                         &task_id_reader,
@@ -308,14 +392,14 @@ fn ssh_exec_worker(
                         Ok(ch) => ch,
                         Err(e) => {
                             println!("[Writer] Lock failed: {:?}", e);
-                            let _ = input_err_tx.send("Lock failed".into());
+                            let _ = input_err_tx.send((FailureKind::Io, "Lock failed".into()));
                             break;
                         }
                     };
 
                     if let Err(e) = ch.write_all(&input) {
                         println!("[Writer] Write failed: {:?}", e);
-                        let _ = input_err_tx.send(format!("{:?}", e));
+                        let _ = input_err_tx.send((FailureKind::Io, format!("{:?}", e)));
                         break;
                     }
 
@@ -343,14 +427,14 @@ fn ssh_exec_worker(
 
     // Check for errors
     if let Ok(err) = output_err_rx.try_recv() {
-        emit_progress(&app, &task_id, "error");
-        cleanup_task(&task_id);
+        emit_progress(app, task_id, "error");
+        cleanup_task(task_id);
         return Err(err);
     }
 
     if let Ok(err) = input_err_rx.try_recv() {
-        emit_progress(&app, &task_id, "error");
-        cleanup_task(&task_id);
+        emit_progress(app, task_id, "error");
+        cleanup_task(task_id);
         return Err(err);
     }
 
@@ -361,10 +445,10 @@ fn ssh_exec_worker(
         ch.exit_status().unwrap_or(-1)
     };
 
-    emit_done(&app, &task_id, exit_code);
-    cleanup_task(&task_id);
+    emit_done(app, task_id, exit_code);
+    cleanup_task(task_id);
 
-    Ok(())
+    Ok(exit_code)
 }
 
 /* =========================
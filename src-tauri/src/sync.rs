@@ -0,0 +1,734 @@
+use glob::Pattern;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use ssh2::{FileStat, OpenFlags, OpenType, Sftp};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs,
+    io::{self, Read, Write},
+    path::{Component, Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tauri::{AppHandle, Emitter};
+
+use crate::sftp::sftp_error;
+use crate::ssh_session::connect_host;
+
+/* =========================
+   CONFIG
+========================= */
+
+const CHUNK_SIZE: usize = 64 * 1024;
+const DEFAULT_DIR_MODE: i32 = 0o755;
+const DEFAULT_FILE_MODE: i32 = 0o644;
+
+/* =========================
+   TASK REGISTRY
+========================= */
+
+static SYNC_TASKS: Lazy<Mutex<HashMap<String, Arc<AtomicBool>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/* =========================
+   MODELS
+========================= */
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncDirection {
+    /// local -> remote
+    Upload,
+    /// remote -> local
+    Download,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncCompare {
+    #[default]
+    SizeMtime,
+    Checksum,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct SyncOptions {
+    pub host_id: i64,
+    pub local_dir: String,
+    pub remote_dir: String,
+    pub direction: SyncDirection,
+    #[serde(default)]
+    pub compare: SyncCompare,
+    /// Glob patterns matched against the relative path and the file name
+    #[serde(default)]
+    pub excludes: Vec<String>,
+    /// Remove destination entries that no longer exist at the source
+    #[serde(default)]
+    pub delete: bool,
+    #[serde(default)]
+    pub preserve_permissions: bool,
+    #[serde(default = "default_true")]
+    pub preserve_mtime: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncAction {
+    Add,
+    Update,
+    Delete,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SyncEntry {
+    pub action: SyncAction,
+    /// Path relative to the synced roots, always `/` separated
+    pub path: String,
+    pub is_dir: bool,
+    pub size: u64,
+}
+
+#[derive(Serialize)]
+pub struct SyncPlan {
+    pub entries: Vec<SyncEntry>,
+    pub adds: usize,
+    pub updates: usize,
+    pub deletes: usize,
+    pub bytes: u64,
+}
+
+#[derive(Clone)]
+struct TreeEntry {
+    is_dir: bool,
+    size: u64,
+    mtime: u64,
+}
+
+type Tree = BTreeMap<String, TreeEntry>;
+
+/* =========================
+   EVENTS
+========================= */
+
+#[derive(Serialize, Clone)]
+struct SyncProgressEvent {
+    task_id: String,
+    index: usize,
+    total: usize,
+    action: SyncAction,
+    path: String,
+}
+
+#[derive(Serialize, Clone)]
+struct SyncDoneEvent {
+    task_id: String,
+    applied: usize,
+    cancelled: bool,
+    error: Option<String>,
+}
+
+/* =========================
+   TAURI COMMANDS
+========================= */
+
+/// Dry run: compares both trees and returns what `sync_apply` would do
+#[tauri::command]
+pub fn sync_plan(options: SyncOptions, app: AppHandle) -> Result<SyncPlan, String> {
+    let sess = connect_host(&app, options.host_id)?;
+    let sftp = sess.sftp().map_err(|e| format!("SFTP init failed: {}", e))?;
+
+    build_plan(&sftp, &options)
+}
+
+/// Applies `entries` from a previous dry run, or a freshly computed plan when omitted
+#[tauri::command]
+pub fn sync_apply(
+    task_id: String,
+    options: SyncOptions,
+    entries: Option<Vec<SyncEntry>>,
+    app: AppHandle,
+) -> Result<(), String> {
+    let cancel = Arc::new(AtomicBool::new(false));
+    SYNC_TASKS
+        .lock()
+        .unwrap()
+        .insert(task_id.clone(), cancel.clone());
+
+    std::thread::spawn(move || {
+        let result = sync_worker(&app, &task_id, &options, entries, &cancel);
+        let (applied, error) = match result {
+            Ok(n) => (n, None),
+            Err(e) => (0, Some(e)),
+        };
+
+        let _ = app.emit(
+            "sync:done",
+            SyncDoneEvent {
+                task_id: task_id.clone(),
+                applied,
+                cancelled: cancel.load(Ordering::Relaxed),
+                error,
+            },
+        );
+        SYNC_TASKS.lock().unwrap().remove(&task_id);
+    });
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn sync_cancel(task_id: String) {
+    if let Some(cancel) = SYNC_TASKS.lock().unwrap().get(&task_id) {
+        cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/* =========================
+   PLAN
+========================= */
+
+fn build_plan(sftp: &Sftp, options: &SyncOptions) -> Result<SyncPlan, String> {
+    let excludes = options
+        .excludes
+        .iter()
+        .map(|p| Pattern::new(p).map_err(|e| format!("Invalid exclude '{}': {}", p, e)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let local_root = PathBuf::from(&options.local_dir);
+    let remote_root = PathBuf::from(&options.remote_dir);
+
+    let mut local = Tree::new();
+    if local_root.is_dir() {
+        walk_local(&local_root, "", &excludes, &mut local)?;
+    } else if options.direction == SyncDirection::Upload {
+        return Err(format!("Local folder not found: {}", options.local_dir));
+    }
+
+    let mut remote = Tree::new();
+    match sftp.stat(&remote_root) {
+        Ok(stat) if stat.is_dir() => walk_remote(sftp, &remote_root, "", &excludes, &mut remote)?,
+        Ok(_) => return Err(format!("Not a folder: {}", options.remote_dir)),
+        Err(_) if options.direction == SyncDirection::Upload => {}
+        Err(e) => return Err(sftp_error(e)),
+    }
+
+    let (src, dst) = match options.direction {
+        SyncDirection::Upload => (&local, &remote),
+        SyncDirection::Download => (&remote, &local),
+    };
+
+    let mut entries = Vec::new();
+
+    for (path, s) in src {
+        let action = match dst.get(path) {
+            None => Some(SyncAction::Add),
+            Some(d) if d.is_dir != s.is_dir => {
+                entries.push(SyncEntry {
+                    action: SyncAction::Delete,
+                    path: path.clone(),
+                    is_dir: d.is_dir,
+                    size: d.size,
+                });
+                Some(SyncAction::Add)
+            }
+            Some(_) if s.is_dir => None,
+            Some(d) => {
+                let changed = match options.compare {
+                    SyncCompare::SizeMtime => s.size != d.size || s.mtime != d.mtime,
+                    SyncCompare::Checksum => {
+                        s.size != d.size
+                            || local_sha256(&local_root.join(path))?
+                                != remote_sha256(sftp, &remote_root.join(path))?
+                    }
+                };
+                changed.then_some(SyncAction::Update)
+            }
+        };
+
+        if let Some(action) = action {
+            entries.push(SyncEntry {
+                action,
+                path: path.clone(),
+                is_dir: s.is_dir,
+                size: if s.is_dir { 0 } else { s.size },
+            });
+        }
+    }
+
+    if options.delete {
+        for (path, d) in dst {
+            if !src.contains_key(path) {
+                entries.push(SyncEntry {
+                    action: SyncAction::Delete,
+                    path: path.clone(),
+                    is_dir: d.is_dir,
+                    size: d.size,
+                });
+            }
+        }
+    }
+
+    order_entries(&mut entries);
+
+    Ok(SyncPlan {
+        adds: entries.iter().filter(|e| e.action == SyncAction::Add).count(),
+        updates: entries.iter().filter(|e| e.action == SyncAction::Update).count(),
+        deletes: entries.iter().filter(|e| e.action == SyncAction::Delete).count(),
+        bytes: entries
+            .iter()
+            .filter(|e| e.action != SyncAction::Delete)
+            .map(|e| e.size)
+            .sum(),
+        entries,
+    })
+}
+
+/// Entries picked by the frontend must be part of a plan built from the same
+/// options; anything else could point outside the synced roots
+fn selected_entries(selected: Vec<SyncEntry>, plan: &[SyncEntry]) -> Result<Vec<SyncEntry>, String> {
+    let planned: HashSet<(&str, bool, bool)> = plan
+        .iter()
+        .map(|e| (e.path.as_str(), e.is_dir, e.action == SyncAction::Delete))
+        .collect();
+
+    for entry in &selected {
+        if !is_relative_inside(&entry.path) {
+            return Err(format!("Invalid sync path '{}'", entry.path));
+        }
+        if !planned.contains(&(entry.path.as_str(), entry.is_dir, entry.action == SyncAction::Delete)) {
+            return Err(format!(
+                "'{}' is no longer part of the sync plan, preview again",
+                entry.path
+            ));
+        }
+    }
+
+    let mut selected = selected;
+    order_entries(&mut selected);
+    Ok(selected)
+}
+
+/// Only plain names, no root, prefix, `.` or `..`
+fn is_relative_inside(path: &str) -> bool {
+    !path.is_empty()
+        && Path::new(path)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+}
+
+/// Deletes first (children before parents) so type changes can be re-added,
+/// then adds/updates with parents before children
+fn order_entries(entries: &mut [SyncEntry]) {
+    entries.sort_by(|a, b| {
+        let a_del = a.action == SyncAction::Delete;
+        let b_del = b.action == SyncAction::Delete;
+        b_del.cmp(&a_del).then_with(|| {
+            if a_del {
+                b.path.cmp(&a.path)
+            } else {
+                a.path.cmp(&b.path)
+            }
+        })
+    });
+}
+
+fn is_excluded(rel: &str, name: &str, excludes: &[Pattern]) -> bool {
+    excludes.iter().any(|p| p.matches(rel) || p.matches(name))
+}
+
+fn join_rel(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", prefix, name)
+    }
+}
+
+/// Symlinks are skipped on both sides
+fn walk_local(dir: &Path, prefix: &str, excludes: &[Pattern], out: &mut Tree) -> Result<(), String> {
+    for item in fs::read_dir(dir).map_err(|e| e.to_string())? {
+        let item = item.map_err(|e| e.to_string())?;
+        let name = item.file_name().to_string_lossy().to_string();
+        let rel = join_rel(prefix, &name);
+        let meta = item.metadata().map_err(|e| e.to_string())?;
+
+        if meta.file_type().is_symlink() || is_excluded(&rel, &name, excludes) {
+            continue;
+        }
+
+        out.insert(
+            rel.clone(),
+            TreeEntry {
+                is_dir: meta.is_dir(),
+                size: if meta.is_dir() { 0 } else { meta.len() },
+                mtime: meta
+                    .modified()
+                    .ok()
+                    .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0),
+            },
+        );
+
+        if meta.is_dir() {
+            walk_local(&item.path(), &rel, excludes, out)?;
+        }
+    }
+    Ok(())
+}
+
+fn walk_remote(
+    sftp: &Sftp,
+    dir: &Path,
+    prefix: &str,
+    excludes: &[Pattern],
+    out: &mut Tree,
+) -> Result<(), String> {
+    for (path, stat) in sftp.readdir(dir).map_err(sftp_error)? {
+        let name = match path.file_name() {
+            Some(n) => n.to_string_lossy().to_string(),
+            None => continue,
+        };
+        if name == "." || name == ".." {
+            continue;
+        }
+
+        let rel = join_rel(prefix, &name);
+        if stat.file_type().is_symlink() || is_excluded(&rel, &name, excludes) {
+            continue;
+        }
+
+        out.insert(
+            rel.clone(),
+            TreeEntry {
+                is_dir: stat.is_dir(),
+                size: stat.size.unwrap_or(0),
+                mtime: stat.mtime.unwrap_or(0),
+            },
+        );
+
+        if stat.is_dir() {
+            walk_remote(sftp, &path, &rel, excludes, out)?;
+        }
+    }
+    Ok(())
+}
+
+fn local_sha256(path: &Path) -> Result<Vec<u8>, String> {
+    let mut file = fs::File::open(path).map_err(|e| e.to_string())?;
+    hash_reader(&mut file).map_err(|e| e.to_string())
+}
+
+fn remote_sha256(sftp: &Sftp, path: &Path) -> Result<Vec<u8>, String> {
+    let mut file = sftp.open(path).map_err(sftp_error)?;
+    hash_reader(&mut file).map_err(|e| e.to_string())
+}
+
+fn hash_reader(reader: &mut impl Read) -> io::Result<Vec<u8>> {
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    loop {
+        let n = reader.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hasher.finalize().to_vec())
+}
+
+/* =========================
+   APPLY
+========================= */
+
+fn sync_worker(
+    app: &AppHandle,
+    task_id: &str,
+    options: &SyncOptions,
+    entries: Option<Vec<SyncEntry>>,
+    cancel: &AtomicBool,
+) -> Result<usize, String> {
+    let sess = connect_host(app, options.host_id)?;
+    let sftp = sess.sftp().map_err(|e| format!("SFTP init failed: {}", e))?;
+
+    let plan = build_plan(&sftp, options)?.entries;
+    let entries = match entries {
+        Some(e) => selected_entries(e, &plan)?,
+        None => plan,
+    };
+
+    let local_root = PathBuf::from(&options.local_dir);
+    let remote_root = PathBuf::from(&options.remote_dir);
+
+    // make sure the destination root exists
+    match options.direction {
+        SyncDirection::Upload => {
+            if sftp.stat(&remote_root).is_err() {
+                sftp.mkdir(&remote_root, DEFAULT_DIR_MODE).map_err(sftp_error)?;
+            }
+        }
+        SyncDirection::Download => fs::create_dir_all(&local_root).map_err(|e| e.to_string())?,
+    }
+
+    let total = entries.len();
+    for (index, entry) in entries.iter().enumerate() {
+        if cancel.load(Ordering::Relaxed) {
+            return Ok(index);
+        }
+
+        let _ = app.emit(
+            "sync:progress",
+            SyncProgressEvent {
+                task_id: task_id.into(),
+                index,
+                total,
+                action: entry.action,
+                path: entry.path.clone(),
+            },
+        );
+
+        let local = local_root.join(&entry.path);
+        let remote = remote_root.join(&entry.path);
+
+        match options.direction {
+            SyncDirection::Upload => apply_upload(&sftp, entry, &local, &remote, options),
+            SyncDirection::Download => ensure_no_symlinks(&local_root, &entry.path)
+                .and_then(|_| apply_download(&sftp, entry, &local, &remote, options)),
+        }
+        .map_err(|e| format!("{}: {}", entry.path, e))?;
+    }
+
+    Ok(total)
+}
+
+fn apply_upload(
+    sftp: &Sftp,
+    entry: &SyncEntry,
+    local: &Path,
+    remote: &Path,
+    options: &SyncOptions,
+) -> Result<(), String> {
+    if entry.action == SyncAction::Delete {
+        return remove_remote(sftp, remote, entry.is_dir);
+    }
+
+    let meta = fs::metadata(local).map_err(|e| e.to_string())?;
+
+    if entry.is_dir {
+        if sftp.stat(remote).is_err() {
+            sftp.mkdir(remote, DEFAULT_DIR_MODE).map_err(sftp_error)?;
+        }
+    } else {
+        let mut src = fs::File::open(local).map_err(|e| e.to_string())?;
+        let mut dst = sftp
+            .open_mode(
+                remote,
+                OpenFlags::WRITE | OpenFlags::CREATE | OpenFlags::TRUNCATE,
+                DEFAULT_FILE_MODE,
+                OpenType::File,
+            )
+            .map_err(sftp_error)?;
+        io::copy(&mut src, &mut dst).map_err(|e| e.to_string())?;
+        dst.flush().map_err(|e| e.to_string())?;
+    }
+
+    let mtime = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs());
+
+    if options.preserve_permissions || options.preserve_mtime {
+        let stat = FileStat {
+            size: None,
+            uid: None,
+            gid: None,
+            perm: options
+                .preserve_permissions
+                .then(|| local_perm(&meta))
+                .filter(|p| *p != 0),
+            atime: if options.preserve_mtime { mtime } else { None },
+            mtime: if options.preserve_mtime { mtime } else { None },
+        };
+        sftp.setstat(remote, stat).map_err(sftp_error)?;
+    }
+
+    Ok(())
+}
+
+fn apply_download(
+    sftp: &Sftp,
+    entry: &SyncEntry,
+    local: &Path,
+    remote: &Path,
+    options: &SyncOptions,
+) -> Result<(), String> {
+    if entry.action == SyncAction::Delete {
+        return if entry.is_dir {
+            fs::remove_dir_all(local).map_err(|e| e.to_string())
+        } else {
+            fs::remove_file(local).map_err(|e| e.to_string())
+        };
+    }
+
+    let stat = sftp.stat(remote).map_err(sftp_error)?;
+
+    if entry.is_dir {
+        fs::create_dir_all(local).map_err(|e| e.to_string())?;
+    } else {
+        let mut src = sftp.open(remote).map_err(sftp_error)?;
+        let mut dst = fs::File::create(local).map_err(|e| e.to_string())?;
+        io::copy(&mut src, &mut dst).map_err(|e| e.to_string())?;
+        dst.flush().map_err(|e| e.to_string())?;
+
+        if options.preserve_mtime {
+            if let Some(mtime) = stat.mtime {
+                dst.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(mtime))
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+    }
+
+    if options.preserve_permissions {
+        if let Some(perm) = stat.perm {
+            set_local_perm(local, perm & 0o7777)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The plan skips local symlinks, so a download could otherwise write or
+/// delete through one, or through a symlinked directory, outside the root
+fn ensure_no_symlinks(root: &Path, rel: &str) -> Result<(), String> {
+    let mut path = root.to_path_buf();
+    for component in Path::new(rel).components() {
+        path.push(component);
+        match fs::symlink_metadata(&path) {
+            Ok(meta) if meta.file_type().is_symlink() => {
+                return Err(format!("{} is a symlink, not following it", path.display()));
+            }
+            Ok(_) => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+    Ok(())
+}
+
+fn remove_remote(sftp: &Sftp, path: &Path, is_dir: bool) -> Result<(), String> {
+    if !is_dir {
+        return sftp.unlink(path).map_err(sftp_error);
+    }
+
+    // children are normally deleted first by the plan order, but a type change
+    // (dir -> file) only lists the dir itself
+    for (child, stat) in sftp.readdir(path).map_err(sftp_error)? {
+        let name = child.file_name().map(|n| n.to_string_lossy().to_string());
+        if matches!(name.as_deref(), Some(".") | Some("..")) {
+            continue;
+        }
+        remove_remote(sftp, &child, stat.is_dir() && !stat.file_type().is_symlink())?;
+    }
+    sftp.rmdir(path).map_err(sftp_error)
+}
+
+#[cfg(unix)]
+fn local_perm(meta: &fs::Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    meta.permissions().mode() & 0o7777
+}
+
+#[cfg(not(unix))]
+fn local_perm(_meta: &fs::Metadata) -> u32 {
+    0
+}
+
+#[cfg(unix)]
+fn set_local_perm(path: &Path, perm: u32) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(perm)).map_err(|e| e.to_string())
+}
+
+#[cfg(not(unix))]
+fn set_local_perm(_path: &Path, _perm: u32) -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(action: SyncAction, path: &str, is_dir: bool) -> SyncEntry {
+        SyncEntry {
+            action,
+            path: path.into(),
+            is_dir,
+            size: 0,
+        }
+    }
+
+    #[test]
+    fn rejects_paths_escaping_the_root() {
+        assert!(is_relative_inside("a/b.txt"));
+        assert!(!is_relative_inside(""));
+        assert!(!is_relative_inside("/etc/passwd"));
+        assert!(!is_relative_inside("../outside"));
+        assert!(!is_relative_inside("a/../../b"));
+        assert!(!is_relative_inside("./a"));
+    }
+
+    #[test]
+    fn keeps_only_planned_entries() {
+        let plan = vec![
+            entry(SyncAction::Add, "a", true),
+            entry(SyncAction::Add, "a/x.txt", false),
+            entry(SyncAction::Delete, "old", true),
+        ];
+
+        let picked = selected_entries(
+            vec![entry(SyncAction::Add, "a/x.txt", false), entry(SyncAction::Delete, "old", true)],
+            &plan,
+        )
+        .unwrap();
+        assert_eq!(picked[0].path, "old");
+        assert_eq!(picked[1].path, "a/x.txt");
+
+        assert!(selected_entries(vec![entry(SyncAction::Delete, "a", true)], &plan).is_err());
+        assert!(selected_entries(vec![entry(SyncAction::Delete, "../home", true)], &plan).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn refuses_to_write_through_symlinks() {
+        use std::os::unix::fs::symlink;
+
+        let base = std::env::temp_dir().join(format!("nethopper-sync-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        let root = base.join("root");
+        let outside = base.join("outside");
+        fs::create_dir_all(root.join("dir")).unwrap();
+        fs::create_dir_all(&outside).unwrap();
+        symlink(outside.join("secret"), root.join("link.txt")).unwrap();
+        symlink(&outside, root.join("linked")).unwrap();
+
+        let plain = ensure_no_symlinks(&root, "dir/new.txt");
+        let missing = ensure_no_symlinks(&root, "new/deeper/file.txt");
+        let file_link = ensure_no_symlinks(&root, "link.txt");
+        let dir_link = ensure_no_symlinks(&root, "linked/file.txt");
+        fs::remove_dir_all(&base).unwrap();
+
+        assert!(plain.is_ok());
+        assert!(missing.is_ok());
+        assert!(file_link.unwrap_err().contains("is a symlink"));
+        assert!(dir_link.unwrap_err().contains("is a symlink"));
+    }
+}
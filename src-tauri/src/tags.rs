@@ -0,0 +1,577 @@
+use rusqlite::{types::Value, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::commands::Host;
+use crate::db::Db;
+
+/* =========================
+   MODELS
+========================= */
+
+#[derive(Serialize)]
+pub struct Tag {
+    pub id: i64,
+    pub name: String,
+    pub host_count: i64,
+}
+
+/// Target of a bulk operation. The parts are combined with OR; an empty
+/// selector selects nothing.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct HostSelector {
+    pub host_ids: Vec<i64>,
+    /// Hosts in these groups and all their sub groups
+    pub group_ids: Vec<i64>,
+    /// e.g. `env:prod AND (role:db OR role:cache) AND NOT legacy`
+    pub tag_expr: Option<String>,
+}
+
+/* =========================
+   TAURI COMMANDS
+========================= */
+
+#[tauri::command]
+pub fn list_tags(db: tauri::State<Db>) -> Result<Vec<Tag>, String> {
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT t.id, t.name, COUNT(h.id)
+             FROM tags t
+             LEFT JOIN host_tags ht ON ht.tag_id = t.id
+             LEFT JOIN hosts h ON h.id = ht.host_id AND h.deleted_at IS NULL
+             GROUP BY t.id ORDER BY t.name",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map([], |r| {
+            Ok(Tag {
+                id: r.get(0)?,
+                name: r.get(1)?,
+                host_count: r.get(2)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.filter_map(Result::ok).collect())
+}
+
+#[tauri::command]
+pub fn get_host_tags(host_id: i64, db: tauri::State<Db>) -> Result<Vec<String>, String> {
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT t.name FROM tags t JOIN host_tags ht ON ht.tag_id = t.id
+             WHERE ht.host_id = ? ORDER BY t.name",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map([host_id], |r| r.get(0))
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.filter_map(Result::ok).collect())
+}
+
+/// Adds `tags` to every selected host, creating missing tags. Returns the number of hosts.
+#[tauri::command]
+pub fn tag_hosts(
+    selector: HostSelector,
+    tags: Vec<String>,
+    db: tauri::State<Db>,
+) -> Result<usize, String> {
+    let tags = normalize_tags(tags)?;
+    let mut conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    let host_ids = resolve_host_selector(&conn, &selector)?;
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    for tag in &tags {
+        tx.execute("INSERT OR IGNORE INTO tags (name) VALUES (?)", [tag])
+            .map_err(|e| e.to_string())?;
+        for host_id in &host_ids {
+            tx.execute(
+                "INSERT OR IGNORE INTO host_tags (host_id, tag_id)
+                 SELECT ?1, id FROM tags WHERE name = ?2",
+                rusqlite::params![host_id, tag],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    Ok(host_ids.len())
+}
+
+/// Removes `tags` from every selected host; tags left without hosts are kept
+#[tauri::command]
+pub fn untag_hosts(
+    selector: HostSelector,
+    tags: Vec<String>,
+    db: tauri::State<Db>,
+) -> Result<usize, String> {
+    let tags = normalize_tags(tags)?;
+    let mut conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    let host_ids = resolve_host_selector(&conn, &selector)?;
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    for tag in &tags {
+        for host_id in &host_ids {
+            tx.execute(
+                "DELETE FROM host_tags
+                 WHERE host_id = ?1 AND tag_id = (SELECT id FROM tags WHERE name = ?2)",
+                rusqlite::params![host_id, tag],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    Ok(host_ids.len())
+}
+
+#[tauri::command]
+pub fn rename_tag(id: i64, name: String, db: tauri::State<Db>) -> Result<(), String> {
+    let name = normalize_tags(vec![name])?.remove(0);
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    conn.execute("UPDATE tags SET name = ? WHERE id = ?", rusqlite::params![name, id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_tag(id: i64, db: tauri::State<Db>) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    conn.execute("DELETE FROM tags WHERE id = ?", [id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_hosts_by_tags(expr: String, db: tauri::State<Db>) -> Result<Vec<Host>, String> {
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    let (filter, params) = compile_tag_expr(&expr)?;
+
+    let sql = format!(
+        "SELECT id, name, host, port, username, auth_type, group_id, favorite
+         FROM hosts WHERE deleted_at IS NULL AND ({}) ORDER BY name",
+        filter
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map(rusqlite::params_from_iter(params), |r| {
+            Ok(Host {
+                id: r.get(0)?,
+                name: r.get(1)?,
+                host: r.get(2)?,
+                port: r.get(3)?,
+                username: r.get(4)?,
+                auth_type: r.get(5)?,
+                group_id: r.get(6)?,
+                favorite: r.get(7)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.filter_map(Result::ok).collect())
+}
+
+/// Host ids a bulk operation on `selector` would touch
+#[tauri::command]
+pub fn select_hosts(selector: HostSelector, db: tauri::State<Db>) -> Result<Vec<i64>, String> {
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    resolve_host_selector(&conn, &selector)
+}
+
+/* =========================
+   INVENTORY
+========================= */
+
+/// Adds `tags` to one host, creating missing tags. Run inside a transaction.
+pub fn add_host_tags(conn: &Connection, host_id: i64, tags: Vec<String>) -> Result<(), String> {
+    if tags.is_empty() {
+        return Ok(());
+    }
+    for tag in normalize_tags(tags)? {
+        conn.execute("INSERT OR IGNORE INTO tags (name) VALUES (?)", [&tag])
+            .map_err(|e| e.to_string())?;
+        conn.execute(
+            "INSERT OR IGNORE INTO host_tags (host_id, tag_id)
+             SELECT ?1, id FROM tags WHERE name = ?2",
+            rusqlite::params![host_id, tag],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Tag names of every host, keyed by host id
+pub fn load_all_host_tags(conn: &Connection) -> Result<HashMap<i64, Vec<String>>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT ht.host_id, t.name FROM host_tags ht JOIN tags t ON t.id = ht.tag_id
+             ORDER BY ht.host_id, t.name",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |r| Ok((r.get::<_, i64>(0)?, r.get::<_, String>(1)?)))
+        .map_err(|e| e.to_string())?;
+
+    let mut out: HashMap<i64, Vec<String>> = HashMap::new();
+    for (host_id, tag) in rows.filter_map(Result::ok) {
+        out.entry(host_id).or_default().push(tag);
+    }
+    Ok(out)
+}
+
+/* =========================
+   SELECTOR
+========================= */
+
+pub fn resolve_host_selector(conn: &Connection, selector: &HostSelector) -> Result<Vec<i64>, String> {
+    let mut parts = Vec::new();
+    let mut params: Vec<Value> = Vec::new();
+
+    if !selector.host_ids.is_empty() {
+        parts.push(format!("id IN ({})", placeholders(selector.host_ids.len())));
+        params.extend(selector.host_ids.iter().map(|id| Value::Integer(*id)));
+    }
+
+    if !selector.group_ids.is_empty() {
+        parts.push(format!(
+            "group_id IN (
+                WITH RECURSIVE subtree(id) AS (
+                    SELECT id FROM groups WHERE id IN ({})
+                    UNION
+                    SELECT g.id FROM groups g JOIN subtree s ON g.parent_id = s.id
+                )
+                SELECT id FROM subtree
+            )",
+            placeholders(selector.group_ids.len())
+        ));
+        params.extend(selector.group_ids.iter().map(|id| Value::Integer(*id)));
+    }
+
+    if let Some(expr) = selector.tag_expr.as_deref().filter(|e| !e.trim().is_empty()) {
+        let (filter, tag_params) = compile_tag_expr(expr)?;
+        parts.push(filter);
+        params.extend(tag_params);
+    }
+
+    if parts.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let sql = format!(
+        "SELECT id FROM hosts WHERE deleted_at IS NULL AND ({}) ORDER BY id",
+        parts.join(" OR ")
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(rusqlite::params_from_iter(params), |r| r.get(0))
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.filter_map(Result::ok).collect())
+}
+
+fn placeholders(n: usize) -> String {
+    vec!["?"; n].join(", ")
+}
+
+/* =========================
+   TAG EXPRESSIONS
+   expr  := and ( OR and )*
+   and   := unary ( [AND] unary )*      adjacent terms are ANDed
+   unary := NOT unary | '(' expr ')' | TAG
+   TAG may use `*` as wildcard, e.g. `env:*`. Keywords are case-insensitive.
+========================= */
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+    Tag(String),
+}
+
+/// Compiles to a WHERE fragment over `hosts` plus its parameters
+pub fn compile_tag_expr(expr: &str) -> Result<(String, Vec<Value>), String> {
+    let tokens = tokenize(expr)?;
+    if tokens.is_empty() {
+        return Err("Tag expression is empty".into());
+    }
+
+    let mut parser = ExprParser {
+        tokens,
+        pos: 0,
+        params: Vec::new(),
+    };
+    let sql = parser.parse_or()?;
+    if parser.pos < parser.tokens.len() {
+        return Err(format!("Unexpected {:?} in tag expression", parser.tokens[parser.pos]));
+    }
+
+    Ok((sql, parser.params))
+}
+
+fn tokenize(expr: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = expr.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            '"' => {
+                chars.next();
+                let mut word = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => word.push(c),
+                        None => return Err("Unterminated quote in tag expression".into()),
+                    }
+                }
+                tokens.push(Token::Tag(word));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '(' || c == ')' {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(match word.to_uppercase().as_str() {
+                    "AND" | "&&" => Token::And,
+                    "OR" | "||" => Token::Or,
+                    "NOT" | "!" => Token::Not,
+                    _ => Token::Tag(word),
+                });
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct ExprParser {
+    tokens: Vec<Token>,
+    pos: usize,
+    params: Vec<Value>,
+}
+
+impl ExprParser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn parse_or(&mut self) -> Result<String, String> {
+        let mut parts = vec![self.parse_and()?];
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            parts.push(self.parse_and()?);
+        }
+        Ok(join(parts, "OR"))
+    }
+
+    fn parse_and(&mut self) -> Result<String, String> {
+        let mut parts = vec![self.parse_unary()?];
+        loop {
+            match self.peek() {
+                Some(Token::And) => {
+                    self.pos += 1;
+                    parts.push(self.parse_unary()?);
+                }
+                Some(Token::Not | Token::Open | Token::Tag(_)) => parts.push(self.parse_unary()?),
+                _ => break,
+            }
+        }
+        Ok(join(parts, "AND"))
+    }
+
+    fn parse_unary(&mut self) -> Result<String, String> {
+        let token = self.tokens.get(self.pos).ok_or("Tag expression ends unexpectedly")?;
+
+        match token {
+            Token::Not => {
+                self.pos += 1;
+                Ok(format!("NOT {}", self.parse_unary()?))
+            }
+            Token::Open => {
+                self.pos += 1;
+                let inner = self.parse_or()?;
+                if self.peek() != Some(&Token::Close) {
+                    return Err("Missing ')' in tag expression".into());
+                }
+                self.pos += 1;
+                Ok(format!("({})", inner))
+            }
+            Token::Tag(name) => {
+                let (cond, value) = if name.contains('*') {
+                    ("t.name LIKE ? ESCAPE '\\'", like_pattern(name))
+                } else {
+                    ("t.name = ?", name.clone())
+                };
+                self.pos += 1;
+                self.params.push(Value::Text(value));
+                Ok(format!(
+                    "id IN (SELECT ht.host_id FROM host_tags ht JOIN tags t ON t.id = ht.tag_id WHERE {})",
+                    cond
+                ))
+            }
+            other => Err(format!("Unexpected {:?} in tag expression", other)),
+        }
+    }
+}
+
+fn join(parts: Vec<String>, op: &str) -> String {
+    if parts.len() == 1 {
+        return parts.into_iter().next().unwrap();
+    }
+    format!("({})", parts.join(&format!(" {} ", op)))
+}
+
+/// `env:*` -> `env:%`, with LIKE's own wildcards escaped
+fn like_pattern(tag: &str) -> String {
+    tag.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+        .replace('*', "%")
+}
+
+fn normalize_tags(tags: Vec<String>) -> Result<Vec<String>, String> {
+    let mut out: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().to_string();
+        if tag.is_empty() {
+            continue;
+        }
+        if tag.contains(|c: char| c.is_whitespace() || c == '(' || c == ')' || c == '*' || c == '"') {
+            return Err(format!("Invalid tag '{}': no spaces, quotes, parentheses or '*'", tag));
+        }
+        if ["AND", "OR", "NOT"].contains(&tag.to_uppercase().as_str()) {
+            return Err(format!("'{}' is reserved in tag expressions", tag));
+        }
+        if !out.iter().any(|t| t.eq_ignore_ascii_case(&tag)) {
+            out.push(tag);
+        }
+    }
+    if out.is_empty() {
+        return Err("No tags given".into());
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 1: env:prod role:db, 2: env:prod role:web legacy, 3: env:dev role:db, 4: untagged
+    fn fixture() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE hosts (id INTEGER PRIMARY KEY);
+             CREATE TABLE tags (id INTEGER PRIMARY KEY, name TEXT NOT NULL UNIQUE COLLATE NOCASE);
+             CREATE TABLE host_tags (host_id INTEGER, tag_id INTEGER);
+             INSERT INTO hosts VALUES (1), (2), (3), (4);
+             INSERT INTO tags VALUES (1, 'env:prod'), (2, 'env:dev'), (3, 'role:db'), (4, 'role:web'),
+                                     (5, 'legacy'), (6, 'a_b');
+             INSERT INTO host_tags VALUES (1, 1), (1, 3), (2, 1), (2, 4), (2, 5), (3, 2), (3, 3);",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn select(conn: &Connection, expr: &str) -> Vec<i64> {
+        let (filter, params) = compile_tag_expr(expr).unwrap();
+        let mut stmt = conn
+            .prepare(&format!("SELECT id FROM hosts WHERE {} ORDER BY id", filter))
+            .unwrap();
+        let rows = stmt
+            .query_map(rusqlite::params_from_iter(params), |r| r.get(0))
+            .unwrap();
+        rows.map(Result::unwrap).collect()
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let conn = fixture();
+        assert_eq!(select(&conn, "env:dev OR env:prod AND role:web"), vec![2, 3]);
+        assert_eq!(select(&conn, "env:prod role:db OR legacy"), vec![1, 2]);
+        assert_eq!(select(&conn, "role:db && env:prod || env:dev"), vec![1, 3]);
+    }
+
+    #[test]
+    fn parentheses_override_precedence() {
+        let conn = fixture();
+        assert_eq!(select(&conn, "(env:dev OR env:prod) AND role:db"), vec![1, 3]);
+        assert_eq!(select(&conn, "env:prod AND (role:db OR (role:web AND NOT legacy))"), vec![1]);
+    }
+
+    #[test]
+    fn not_applies_to_the_next_term() {
+        let conn = fixture();
+        assert_eq!(select(&conn, "NOT legacy"), vec![1, 3, 4]);
+        assert_eq!(select(&conn, "not legacy AND env:prod"), vec![1]);
+        assert_eq!(select(&conn, "NOT (env:prod OR env:dev)"), vec![4]);
+        assert_eq!(select(&conn, "! ! legacy"), vec![2]);
+    }
+
+    #[test]
+    fn wildcards_and_quotes() {
+        let conn = fixture();
+        assert_eq!(select(&conn, "env:*"), vec![1, 2, 3]);
+        assert_eq!(select(&conn, "ENV:PROD"), vec![1, 2]);
+        // `_` is literal, not LIKE's single character wildcard
+        conn.execute_batch(
+            "INSERT INTO hosts VALUES (5), (6);
+             INSERT INTO tags VALUES (7, 'axb');
+             INSERT INTO host_tags VALUES (5, 7), (6, 6);",
+        )
+        .unwrap();
+        assert_eq!(select(&conn, "a_*"), vec![6]);
+        assert_eq!(select(&conn, "a*b"), vec![5, 6]);
+        assert_eq!(select(&conn, "\"legacy\" and \"role:web\""), vec![2]);
+    }
+
+    #[test]
+    fn rejects_unbalanced_and_incomplete_input() {
+        for (expr, error) in [
+            ("", "Tag expression is empty"),
+            ("   ", "Tag expression is empty"),
+            ("(env:prod", "Missing ')' in tag expression"),
+            ("((a OR b)", "Missing ')' in tag expression"),
+            ("env:prod)", "Unexpected Close in tag expression"),
+            ("()", "Unexpected Close in tag expression"),
+            ("env:prod AND", "Tag expression ends unexpectedly"),
+            ("NOT", "Tag expression ends unexpectedly"),
+            ("OR legacy", "Unexpected Or in tag expression"),
+            ("a OR OR b", "Unexpected Or in tag expression"),
+            ("\"open", "Unterminated quote in tag expression"),
+        ] {
+            assert_eq!(compile_tag_expr(expr).err().as_deref(), Some(error), "{}", expr);
+        }
+    }
+
+    #[test]
+    fn like_patterns_escape_sql_wildcards() {
+        assert_eq!(like_pattern("env:*"), "env:%");
+        assert_eq!(like_pattern("100%_x*"), "100\\%\\_x%");
+        assert_eq!(like_pattern("a\\b"), "a\\\\b");
+    }
+}
@@ -0,0 +1,649 @@
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use ssh2::{OpenFlags, OpenType, Sftp};
+use std::{
+    fs::{self, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::db::Db;
+use crate::settings::{get_setting, set_setting};
+use crate::sftp::sftp_error;
+use crate::ssh_session::connect_host;
+
+/* =========================
+   CONFIG
+========================= */
+
+const CHUNK_SIZE: usize = 64 * 1024;
+const PROGRESS_INTERVAL_MS: u64 = 200;
+const DEFAULT_PARALLELISM: usize = 3;
+const MAX_PARALLELISM: usize = 16;
+const PARALLELISM_KEY: &str = "transfer.parallelism";
+const DEFAULT_FILE_MODE: i32 = 0o644;
+
+/* =========================
+   MODELS
+========================= */
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TransferDirection {
+    Upload,
+    Download,
+}
+
+/// What to do when the destination already exists
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictPolicy {
+    #[default]
+    Overwrite,
+    Skip,
+    Rename,
+    /// Continue a partial file from its current size
+    Resume,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TransferStatus {
+    Queued,
+    Running,
+    Paused,
+    Completed,
+    Skipped,
+    Cancelled,
+    Failed,
+}
+
+impl TransferStatus {
+    fn is_finished(self) -> bool {
+        matches!(
+            self,
+            TransferStatus::Completed
+                | TransferStatus::Skipped
+                | TransferStatus::Cancelled
+                | TransferStatus::Failed
+        )
+    }
+}
+
+#[derive(Serialize, Clone)]
+pub struct TransferInfo {
+    pub job_id: String,
+    pub host_id: i64,
+    pub direction: TransferDirection,
+    pub local_path: String,
+    pub remote_path: String,
+    pub conflict: ConflictPolicy,
+    pub status: TransferStatus,
+    pub bytes: u64,
+    pub total: u64,
+    pub error: Option<String>,
+}
+
+struct TransferJob {
+    info: Mutex<TransferInfo>,
+    cancel: AtomicBool,
+    pause: AtomicBool,
+    /// End of the destination range this job has written; a re-queued job
+    /// only continues a partial file it wrote itself
+    written: AtomicU64,
+}
+
+impl TransferJob {
+    fn snapshot(&self) -> TransferInfo {
+        self.info.lock().unwrap().clone()
+    }
+}
+
+/* =========================
+   GLOBAL QUEUE
+========================= */
+
+struct TransferQueue {
+    jobs: Vec<Arc<TransferJob>>,
+    running: usize,
+    parallelism: Option<usize>,
+}
+
+static QUEUE: Lazy<Mutex<TransferQueue>> = Lazy::new(|| {
+    Mutex::new(TransferQueue {
+        jobs: Vec::new(),
+        running: 0,
+        parallelism: None,
+    })
+});
+
+static JOB_SEQ: AtomicU64 = AtomicU64::new(1);
+
+/* =========================
+   EVENTS
+========================= */
+
+#[derive(Serialize, Clone)]
+struct TransferProgressEvent {
+    job_id: String,
+    bytes: u64,
+    total: u64,
+    /// bytes per second
+    rate: f64,
+    eta_secs: Option<u64>,
+}
+
+#[derive(Serialize, Clone)]
+struct TransferStatusEvent {
+    job_id: String,
+    status: TransferStatus,
+    error: Option<String>,
+}
+
+/* =========================
+   TAURI COMMANDS
+========================= */
+
+#[tauri::command]
+pub fn transfer_upload(
+    host_id: i64,
+    local_path: String,
+    remote_path: String,
+    conflict: Option<ConflictPolicy>,
+    app: AppHandle,
+) -> Result<String, String> {
+    if !Path::new(&local_path).is_file() {
+        return Err(format!("Local file not found: {}", local_path));
+    }
+
+    Ok(enqueue(
+        &app,
+        host_id,
+        TransferDirection::Upload,
+        local_path,
+        remote_path,
+        conflict.unwrap_or_default(),
+    ))
+}
+
+#[tauri::command]
+pub fn transfer_download(
+    host_id: i64,
+    remote_path: String,
+    local_path: String,
+    conflict: Option<ConflictPolicy>,
+    app: AppHandle,
+) -> Result<String, String> {
+    Ok(enqueue(
+        &app,
+        host_id,
+        TransferDirection::Download,
+        local_path,
+        remote_path,
+        conflict.unwrap_or_default(),
+    ))
+}
+
+#[tauri::command]
+pub fn transfer_list() -> Vec<TransferInfo> {
+    QUEUE
+        .lock()
+        .unwrap()
+        .jobs
+        .iter()
+        .map(|j| j.snapshot())
+        .collect()
+}
+
+#[tauri::command]
+pub fn transfer_cancel(job_id: String, app: AppHandle) -> Result<(), String> {
+    let job = find_job(&job_id)?;
+    job.cancel.store(true, Ordering::Relaxed);
+
+    // queued or paused jobs have no worker to notice the flag
+    let status = job.snapshot().status;
+    if matches!(status, TransferStatus::Queued | TransferStatus::Paused) {
+        set_status(&app, &job, TransferStatus::Cancelled, None);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn transfer_pause(job_id: String, app: AppHandle) -> Result<(), String> {
+    let job = find_job(&job_id)?;
+
+    match job.snapshot().status {
+        TransferStatus::Running => job.pause.store(true, Ordering::Relaxed),
+        TransferStatus::Queued => set_status(&app, &job, TransferStatus::Paused, None),
+        _ => return Err("Transfer is not active".into()),
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn transfer_resume(job_id: String, app: AppHandle) -> Result<(), String> {
+    let job = find_job(&job_id)?;
+
+    match job.snapshot().status {
+        TransferStatus::Paused | TransferStatus::Failed => {
+            job.pause.store(false, Ordering::Relaxed);
+            job.cancel.store(false, Ordering::Relaxed);
+            set_status(&app, &job, TransferStatus::Queued, None);
+            pump(&app);
+            Ok(())
+        }
+        _ => Err("Only paused or failed transfers can be resumed".into()),
+    }
+}
+
+#[tauri::command]
+pub fn transfer_clear_finished() {
+    QUEUE
+        .lock()
+        .unwrap()
+        .jobs
+        .retain(|j| !j.snapshot().status.is_finished());
+}
+
+#[tauri::command]
+pub fn transfer_get_parallelism(app: AppHandle) -> usize {
+    current_parallelism(&app)
+}
+
+#[tauri::command]
+pub fn transfer_set_parallelism(parallelism: usize, app: AppHandle) -> Result<(), String> {
+    if !(1..=MAX_PARALLELISM).contains(&parallelism) {
+        return Err(format!("Parallelism must be between 1 and {}", MAX_PARALLELISM));
+    }
+
+    {
+        let db = app.state::<Db>();
+        let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+        set_setting(&conn, PARALLELISM_KEY, &parallelism)?;
+    }
+
+    QUEUE.lock().unwrap().parallelism = Some(parallelism);
+    pump(&app);
+    Ok(())
+}
+
+/* =========================
+   SCHEDULER
+========================= */
+
+fn enqueue(
+    app: &AppHandle,
+    host_id: i64,
+    direction: TransferDirection,
+    local_path: String,
+    remote_path: String,
+    conflict: ConflictPolicy,
+) -> String {
+    let job_id = format!(
+        "transfer-{}-{}",
+        chrono::Utc::now().timestamp_millis(),
+        JOB_SEQ.fetch_add(1, Ordering::Relaxed)
+    );
+
+    let job = Arc::new(TransferJob {
+        info: Mutex::new(TransferInfo {
+            job_id: job_id.clone(),
+            host_id,
+            direction,
+            local_path,
+            remote_path,
+            conflict,
+            status: TransferStatus::Queued,
+            bytes: 0,
+            total: 0,
+            error: None,
+        }),
+        cancel: AtomicBool::new(false),
+        pause: AtomicBool::new(false),
+        written: AtomicU64::new(0),
+    });
+
+    QUEUE.lock().unwrap().jobs.push(job);
+    emit_status(app, &job_id, TransferStatus::Queued, None);
+    pump(app);
+
+    job_id
+}
+
+fn current_parallelism(app: &AppHandle) -> usize {
+    if let Some(p) = QUEUE.lock().unwrap().parallelism {
+        return p;
+    }
+
+    let stored: Option<usize> = {
+        let db = app.state::<Db>();
+        let conn = db.conn.lock().ok();
+        conn.and_then(|c| get_setting(&c, PARALLELISM_KEY).ok().flatten())
+    };
+    let p = stored.unwrap_or(DEFAULT_PARALLELISM);
+
+    QUEUE.lock().unwrap().parallelism = Some(p);
+    p
+}
+
+/// Starts queued jobs until the parallelism limit is reached
+fn pump(app: &AppHandle) {
+    let limit = current_parallelism(app);
+    let mut queue = QUEUE.lock().unwrap();
+
+    while queue.running < limit {
+        let next = queue
+            .jobs
+            .iter()
+            .find(|j| j.snapshot().status == TransferStatus::Queued)
+            .cloned();
+
+        let Some(job) = next else { break };
+
+        queue.running += 1;
+        job.info.lock().unwrap().status = TransferStatus::Running;
+
+        let app = app.clone();
+        std::thread::spawn(move || {
+            let _slot = WorkerSlot {
+                app: app.clone(),
+                job: job.clone(),
+            };
+            let job_id = job.snapshot().job_id;
+            emit_status(&app, &job_id, TransferStatus::Running, None);
+
+            let outcome = run_job(&app, &job);
+            let (status, error) = match outcome {
+                Ok(status) => (status, None),
+                Err(e) if job.cancel.load(Ordering::Relaxed) => {
+                    println!("[Transfer] {} cancelled: {}", job_id, e);
+                    (TransferStatus::Cancelled, None)
+                }
+                Err(e) => (TransferStatus::Failed, Some(e)),
+            };
+            set_status(&app, &job, status, error);
+        });
+    }
+}
+
+/// Frees the worker slot when the worker ends, including by panic, so a
+/// crashed transfer cannot shrink the pool for the rest of the session
+struct WorkerSlot {
+    app: AppHandle,
+    job: Arc<TransferJob>,
+}
+
+impl Drop for WorkerSlot {
+    fn drop(&mut self) {
+        QUEUE.lock().unwrap_or_else(|e| e.into_inner()).running -= 1;
+
+        if !std::thread::panicking() {
+            pump(&self.app);
+            return;
+        }
+
+        let job_id = {
+            let mut info = self.job.info.lock().unwrap_or_else(|e| e.into_inner());
+            info.status = TransferStatus::Failed;
+            info.error = Some("Transfer worker crashed".into());
+            info.job_id.clone()
+        };
+        emit_status(
+            &self.app,
+            &job_id,
+            TransferStatus::Failed,
+            Some("Transfer worker crashed".into()),
+        );
+
+        // a second panic while unwinding would abort, so the queue moves on
+        // from a fresh thread
+        let app = self.app.clone();
+        std::thread::spawn(move || pump(&app));
+    }
+}
+
+/* =========================
+   WORKER
+========================= */
+
+fn run_job(app: &AppHandle, job: &TransferJob) -> Result<TransferStatus, String> {
+    let info = job.snapshot();
+
+    let sess = connect_host(app, info.host_id)?;
+    let sftp = sess.sftp().map_err(|e| format!("SFTP init failed: {}", e))?;
+
+    match info.direction {
+        TransferDirection::Upload => upload(app, job, &sftp),
+        TransferDirection::Download => download(app, job, &sftp),
+    }
+}
+
+fn upload(app: &AppHandle, job: &TransferJob, sftp: &Sftp) -> Result<TransferStatus, String> {
+    let info = job.snapshot();
+    let local = PathBuf::from(&info.local_path);
+    let total = fs::metadata(&local).map_err(|e| e.to_string())?.len();
+
+    let mut remote = PathBuf::from(&info.remote_path);
+    let existing = sftp.stat(&remote).ok().and_then(|s| s.size);
+
+    let written = job.written.load(Ordering::Relaxed);
+
+    let offset = match (existing, info.conflict) {
+        (None, _) => 0,
+        // a resumed job continues what it wrote, anything else gets the conflict policy
+        (Some(size), _) if written > 0 => resume_offset(size, total).min(written),
+        (Some(size), ConflictPolicy::Resume) => resume_offset(size, total),
+        (Some(_), ConflictPolicy::Overwrite) => 0,
+        (Some(_), ConflictPolicy::Skip) => return Ok(TransferStatus::Skipped),
+        (Some(_), ConflictPolicy::Rename) => {
+            remote = free_name(&remote, |p| sftp.stat(p).is_ok());
+            job.info.lock().unwrap().remote_path = remote.to_string_lossy().to_string();
+            0
+        }
+    };
+
+    let flags = if offset > 0 {
+        OpenFlags::WRITE | OpenFlags::CREATE
+    } else {
+        OpenFlags::WRITE | OpenFlags::CREATE | OpenFlags::TRUNCATE
+    };
+    let mut dst = sftp
+        .open_mode(&remote, flags, DEFAULT_FILE_MODE, OpenType::File)
+        .map_err(sftp_error)?;
+    dst.seek(SeekFrom::Start(offset)).map_err(|e| e.to_string())?;
+
+    let mut src = fs::File::open(&local).map_err(|e| e.to_string())?;
+    src.seek(SeekFrom::Start(offset)).map_err(|e| e.to_string())?;
+
+    copy_with_progress(app, job, &mut src, &mut dst, offset, total)
+}
+
+fn download(app: &AppHandle, job: &TransferJob, sftp: &Sftp) -> Result<TransferStatus, String> {
+    let info = job.snapshot();
+    let remote = PathBuf::from(&info.remote_path);
+    let total = sftp
+        .stat(&remote)
+        .map_err(sftp_error)?
+        .size
+        .unwrap_or(0);
+
+    let mut local = PathBuf::from(&info.local_path);
+    let existing = fs::metadata(&local).ok().map(|m| m.len());
+
+    let written = job.written.load(Ordering::Relaxed);
+
+    let offset = match (existing, info.conflict) {
+        (None, _) => 0,
+        // a resumed job continues what it wrote, anything else gets the conflict policy
+        (Some(size), _) if written > 0 => resume_offset(size, total).min(written),
+        (Some(size), ConflictPolicy::Resume) => resume_offset(size, total),
+        (Some(_), ConflictPolicy::Overwrite) => 0,
+        (Some(_), ConflictPolicy::Skip) => return Ok(TransferStatus::Skipped),
+        (Some(_), ConflictPolicy::Rename) => {
+            local = free_name(&local, |p| p.exists());
+            job.info.lock().unwrap().local_path = local.to_string_lossy().to_string();
+            0
+        }
+    };
+
+    if let Some(parent) = local.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let mut dst = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(offset == 0)
+        .open(&local)
+        .map_err(|e| e.to_string())?;
+    dst.seek(SeekFrom::Start(offset)).map_err(|e| e.to_string())?;
+
+    let mut src = sftp.open(&remote).map_err(sftp_error)?;
+    src.seek(SeekFrom::Start(offset)).map_err(|e| e.to_string())?;
+
+    copy_with_progress(app, job, &mut src, &mut dst, offset, total)
+}
+
+fn copy_with_progress(
+    app: &AppHandle,
+    job: &TransferJob,
+    src: &mut impl Read,
+    dst: &mut impl Write,
+    offset: u64,
+    total: u64,
+) -> Result<TransferStatus, String> {
+    let job_id = job.snapshot().job_id;
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    let mut bytes = offset;
+    let started = Instant::now();
+    let mut last_emit = Instant::now();
+
+    {
+        let mut info = job.info.lock().unwrap();
+        info.bytes = bytes;
+        info.total = total;
+    }
+    job.written.store(bytes, Ordering::Relaxed);
+
+    loop {
+        if job.cancel.load(Ordering::Relaxed) {
+            dst.flush().ok();
+            return Ok(TransferStatus::Cancelled);
+        }
+        if job.pause.swap(false, Ordering::Relaxed) {
+            dst.flush().ok();
+            return Ok(TransferStatus::Paused);
+        }
+
+        let n = src.read(&mut buf).map_err(|e| e.to_string())?;
+        if n == 0 {
+            break;
+        }
+        dst.write_all(&buf[..n]).map_err(|e| e.to_string())?;
+        bytes += n as u64;
+        job.info.lock().unwrap().bytes = bytes;
+        job.written.store(bytes, Ordering::Relaxed);
+
+        if last_emit.elapsed() >= Duration::from_millis(PROGRESS_INTERVAL_MS) {
+            emit_progress(app, &job_id, bytes - offset, bytes, total, started);
+            last_emit = Instant::now();
+        }
+    }
+
+    dst.flush().map_err(|e| e.to_string())?;
+    emit_progress(app, &job_id, bytes - offset, bytes, total, started);
+    Ok(TransferStatus::Completed)
+}
+
+/* =========================
+   HELPERS
+========================= */
+
+fn find_job(job_id: &str) -> Result<Arc<TransferJob>, String> {
+    QUEUE
+        .lock()
+        .unwrap()
+        .jobs
+        .iter()
+        .find(|j| j.snapshot().job_id == job_id)
+        .cloned()
+        .ok_or_else(|| "Transfer not found".to_string())
+}
+
+/// Where to continue a destination of `size` bytes. A destination larger than
+/// the source is stale and written again from the start.
+fn resume_offset(size: u64, total: u64) -> u64 {
+    if size > total {
+        0
+    } else {
+        size
+    }
+}
+
+/// `name.ext` -> `name (1).ext`, `name (2).ext`, ... until `exists` says it is free
+fn free_name(path: &Path, exists: impl Fn(&Path) -> bool) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let ext = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+
+    (1..)
+        .map(|n| path.with_file_name(format!("{} ({}){}", stem, n, ext)))
+        .find(|p| !exists(p))
+        .unwrap()
+}
+
+fn set_status(app: &AppHandle, job: &TransferJob, status: TransferStatus, error: Option<String>) {
+    let job_id = {
+        let mut info = job.info.lock().unwrap();
+        info.status = status;
+        info.error = error.clone();
+        info.job_id.clone()
+    };
+    emit_status(app, &job_id, status, error);
+}
+
+fn emit_status(app: &AppHandle, job_id: &str, status: TransferStatus, error: Option<String>) {
+    let _ = app.emit(
+        "transfer:status",
+        TransferStatusEvent {
+            job_id: job_id.into(),
+            status,
+            error,
+        },
+    );
+}
+
+fn emit_progress(
+    app: &AppHandle,
+    job_id: &str,
+    moved: u64,
+    bytes: u64,
+    total: u64,
+    started: Instant,
+) {
+    let elapsed = started.elapsed().as_secs_f64();
+    let rate = if elapsed > 0.0 { moved as f64 / elapsed } else { 0.0 };
+    let eta_secs = (rate > 0.0).then(|| (total.saturating_sub(bytes) as f64 / rate).ceil() as u64);
+
+    let _ = app.emit(
+        "transfer:progress",
+        TransferProgressEvent {
+            job_id: job_id.into(),
+            bytes,
+            total,
+            rate,
+            eta_secs,
+        },
+    );
+}
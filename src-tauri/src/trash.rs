@@ -0,0 +1,501 @@
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::{thread, time::Duration};
+use tauri::{AppHandle, Manager};
+
+use crate::db::Db;
+use crate::settings::{get_setting, set_setting};
+
+/* =========================
+   CONFIG
+========================= */
+
+const SETTINGS_KEY: &str = "trash";
+/// How often the background thread purges expired trash
+const PURGE_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const MAX_TRASH_DEPTH: i64 = 256;
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct TrashPolicy {
+    /// Entries older than this are purged automatically, 0 keeps them forever
+    pub retention_days: u32,
+}
+
+impl Default for TrashPolicy {
+    fn default() -> Self {
+        Self { retention_days: 30 }
+    }
+}
+
+/* =========================
+   MODELS
+========================= */
+
+#[derive(Serialize)]
+pub struct TrashItem {
+    pub id: i64,
+    /// `group` or `host`
+    pub kind: String,
+    pub ref_id: i64,
+    pub name: String,
+    /// Groups and hosts deleted together with this entry, the entry included
+    pub group_count: i64,
+    pub host_count: i64,
+    pub deleted_at: String,
+}
+
+#[derive(Serialize)]
+pub struct RestoreResult {
+    pub restored_groups: usize,
+    pub restored_hosts: usize,
+    /// The original parent is still in the trash, so the item was restored
+    /// to the top level instead
+    pub moved_to_top_level: bool,
+}
+
+/* =========================
+   TAURI COMMANDS
+========================= */
+
+/// Moves the group, all its sub groups and their hosts into the trash.
+/// Returns the id of the trash entry.
+#[tauri::command]
+pub fn delete_group_recursive(id: i64, db: tauri::State<Db>) -> Result<i64, String> {
+    let mut conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let trash_id = trash_group(&tx, id)?;
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(trash_id)
+}
+
+/// Newest first
+#[tauri::command]
+pub fn list_trash(db: tauri::State<Db>) -> Result<Vec<TrashItem>, String> {
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, kind, ref_id, name, group_count, host_count, deleted_at
+             FROM trash ORDER BY deleted_at DESC, id DESC",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map([], |r| {
+            Ok(TrashItem {
+                id: r.get(0)?,
+                kind: r.get(1)?,
+                ref_id: r.get(2)?,
+                name: r.get(3)?,
+                group_count: r.get(4)?,
+                host_count: r.get(5)?,
+                deleted_at: r.get(6)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.filter_map(Result::ok).collect())
+}
+
+#[tauri::command]
+pub fn restore_trash_item(id: i64, db: tauri::State<Db>) -> Result<RestoreResult, String> {
+    let mut conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    restore_entry(&mut conn, id)
+}
+
+fn restore_entry(conn: &mut Connection, id: i64) -> Result<RestoreResult, String> {
+    let (kind, ref_id) = load_entry(conn, id)?;
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+    // the parent may have been trashed separately after this entry
+    let (table, parent_column) = if kind == "group" {
+        ("groups", "parent_id")
+    } else {
+        ("hosts", "group_id")
+    };
+    let moved_to_top_level = tx
+        .execute(
+            &format!(
+                "UPDATE {table} SET {col} = NULL
+                 WHERE id = ?1 AND {col} IN (SELECT id FROM groups WHERE deleted_at IS NOT NULL)",
+                table = table,
+                col = parent_column
+            ),
+            [ref_id],
+        )
+        .map_err(|e| e.to_string())?
+        > 0;
+
+    let restored_groups = tx
+        .execute(
+            "UPDATE groups SET deleted_at = NULL, trash_id = NULL WHERE trash_id = ?",
+            [id],
+        )
+        .map_err(|e| e.to_string())?;
+    let restored_hosts = tx
+        .execute(
+            "UPDATE hosts SET deleted_at = NULL, trash_id = NULL WHERE trash_id = ?",
+            [id],
+        )
+        .map_err(|e| e.to_string())?;
+    tx.execute("DELETE FROM trash WHERE id = ?", [id])
+        .map_err(|e| e.to_string())?;
+
+    tx.commit().map_err(|e| e.to_string())?;
+
+    Ok(RestoreResult {
+        restored_groups,
+        restored_hosts,
+        moved_to_top_level,
+    })
+}
+
+/// Permanently deletes the entry's groups and hosts
+#[tauri::command]
+pub fn purge_trash_item(id: i64, db: tauri::State<Db>) -> Result<(), String> {
+    let mut conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    load_entry(&conn, id)?;
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    purge_entries(&tx, "id = ?1", rusqlite::params![id])?;
+    tx.commit().map_err(|e| e.to_string())
+}
+
+/// Returns the number of entries purged
+#[tauri::command]
+pub fn empty_trash(db: tauri::State<Db>) -> Result<usize, String> {
+    let mut conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let purged = purge_entries(&tx, "1 = 1", rusqlite::params![])?;
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(purged)
+}
+
+#[tauri::command]
+pub fn get_trash_policy(db: tauri::State<Db>) -> Result<TrashPolicy, String> {
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    Ok(get_setting(&conn, SETTINGS_KEY)?.unwrap_or_default())
+}
+
+/// Saving applies the new retention right away
+#[tauri::command]
+pub fn set_trash_policy(policy: TrashPolicy, db: tauri::State<Db>) -> Result<usize, String> {
+    let mut conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    set_setting(&conn, SETTINGS_KEY, &policy)?;
+    purge_expired(&mut conn, &policy)
+}
+
+/* =========================
+   SOFT DELETE
+========================= */
+
+/// Trashes the group with every sub group and host below it that is not
+/// already in the trash. Run inside a transaction.
+pub fn trash_group(conn: &Connection, id: i64) -> Result<i64, String> {
+    let name: String = conn
+        .query_row(
+            "SELECT name FROM groups WHERE id = ? AND deleted_at IS NULL",
+            [id],
+            |r| r.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?
+        .ok_or("Group not found")?;
+
+    conn.execute(
+        "INSERT INTO trash (kind, ref_id, name) VALUES ('group', ?, ?)",
+        rusqlite::params![id, name],
+    )
+    .map_err(|e| e.to_string())?;
+    let trash_id = conn.last_insert_rowid();
+
+    let subtree = "WITH RECURSIVE sub(id, lvl) AS (
+                       SELECT ?1, 0
+                       UNION
+                       SELECT g.id, s.lvl + 1 FROM groups g JOIN sub s ON g.parent_id = s.id
+                       WHERE s.lvl < ?3 AND g.deleted_at IS NULL
+                   )";
+
+    let host_count = conn
+        .execute(
+            &format!(
+                "{} UPDATE hosts SET deleted_at = CURRENT_TIMESTAMP, trash_id = ?2
+                 WHERE group_id IN (SELECT id FROM sub) AND deleted_at IS NULL",
+                subtree
+            ),
+            rusqlite::params![id, trash_id, MAX_TRASH_DEPTH],
+        )
+        .map_err(|e| e.to_string())?;
+    let group_count = conn
+        .execute(
+            &format!(
+                "{} UPDATE groups SET deleted_at = CURRENT_TIMESTAMP, trash_id = ?2
+                 WHERE id IN (SELECT id FROM sub) AND deleted_at IS NULL",
+                subtree
+            ),
+            rusqlite::params![id, trash_id, MAX_TRASH_DEPTH],
+        )
+        .map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "UPDATE trash SET group_count = ?, host_count = ? WHERE id = ?",
+        rusqlite::params![group_count as i64, host_count as i64, trash_id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(trash_id)
+}
+
+pub fn trash_host(conn: &Connection, id: i64) -> Result<i64, String> {
+    let name: String = conn
+        .query_row(
+            "SELECT name FROM hosts WHERE id = ? AND deleted_at IS NULL",
+            [id],
+            |r| r.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?
+        .ok_or("Host not found")?;
+
+    conn.execute(
+        "INSERT INTO trash (kind, ref_id, name, host_count) VALUES ('host', ?, ?, 1)",
+        rusqlite::params![id, name],
+    )
+    .map_err(|e| e.to_string())?;
+    let trash_id = conn.last_insert_rowid();
+
+    conn.execute(
+        "UPDATE hosts SET deleted_at = CURRENT_TIMESTAMP, trash_id = ? WHERE id = ?",
+        rusqlite::params![trash_id, id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(trash_id)
+}
+
+/* =========================
+   AUTO PURGE
+========================= */
+
+/// Purges expired entries at launch and then periodically
+pub fn start_trash_auto_purge(app: &AppHandle) {
+    let app = app.clone();
+    thread::spawn(move || loop {
+        if let Err(e) = auto_purge(&app) {
+            println!("[Trash] Automatic purge failed: {}", e);
+        }
+        thread::sleep(PURGE_CHECK_INTERVAL);
+    });
+}
+
+fn auto_purge(app: &AppHandle) -> Result<(), String> {
+    let db = app.state::<Db>();
+    let mut conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    let policy: TrashPolicy = get_setting(&conn, SETTINGS_KEY)?.unwrap_or_default();
+
+    let purged = purge_expired(&mut conn, &policy)?;
+    if purged > 0 {
+        println!("[Trash] Purged {} expired entries", purged);
+    }
+    Ok(())
+}
+
+fn purge_expired(conn: &mut Connection, policy: &TrashPolicy) -> Result<usize, String> {
+    if policy.retention_days == 0 {
+        return Ok(0);
+    }
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let purged = purge_entries(
+        &tx,
+        "deleted_at <= datetime('now', '-' || ?1 || ' days')",
+        rusqlite::params![policy.retention_days],
+    )?;
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(purged)
+}
+
+/* =========================
+   HELPERS
+========================= */
+
+fn load_entry(conn: &Connection, id: i64) -> Result<(String, i64), String> {
+    conn.query_row("SELECT kind, ref_id FROM trash WHERE id = ?", [id], |r| {
+        Ok((r.get(0)?, r.get(1)?))
+    })
+    .optional()
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| "Trash entry not found".to_string())
+}
+
+/// Deletes the rows of the matching entries. Deleting a group cascades to
+/// everything below it, including items trashed separately before it, so
+/// entries left without rows are removed afterwards. Run inside a transaction.
+fn purge_entries(conn: &Connection, filter: &str, params: &[&dyn rusqlite::ToSql]) -> Result<usize, String> {
+    let selected = format!("SELECT id FROM trash WHERE {}", filter);
+
+    conn.execute(
+        &format!("DELETE FROM hosts WHERE trash_id IN ({})", selected),
+        params,
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute(
+        &format!("DELETE FROM groups WHERE trash_id IN ({})", selected),
+        params,
+    )
+    .map_err(|e| e.to_string())?;
+    let purged = conn
+        .execute(&format!("DELETE FROM trash WHERE {}", filter), params)
+        .map_err(|e| e.to_string())?;
+
+    conn.execute_batch(
+        "DELETE FROM trash
+         WHERE (kind = 'group' AND ref_id NOT IN (SELECT id FROM groups))
+            OR (kind = 'host' AND ref_id NOT IN (SELECT id FROM hosts))",
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(purged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    /// 1 Prod > 2 DB; host 1 in Prod, hosts 2 and 3 in DB
+    fn fixture() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+        crate::migrations::run_migrations(&mut conn, Path::new("/tmp")).unwrap();
+        conn.execute_batch(
+            "INSERT INTO groups (id, name, parent_id) VALUES (1, 'Prod', NULL), (2, 'DB', 1);
+             INSERT INTO hosts (id, name, host, port, username, auth_type, group_id) VALUES
+                 (1, 'web', 'web.lan', 22, 'ops', 'key', 1),
+                 (2, 'db1', 'db1.lan', 22, 'ops', 'key', 2),
+                 (3, 'db2', 'db2.lan', 22, 'ops', 'key', 2);",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn count(conn: &Connection, sql: &str) -> i64 {
+        conn.query_row(sql, [], |r| r.get(0)).unwrap()
+    }
+
+    fn live_hosts(conn: &Connection) -> i64 {
+        count(conn, "SELECT COUNT(*) FROM hosts WHERE deleted_at IS NULL")
+    }
+
+    #[test]
+    fn host_trashed_before_its_group_stays_separate() {
+        let mut conn = fixture();
+        let host_entry = trash_host(&conn, 2).unwrap();
+        let group_entry = trash_group(&conn, 1).unwrap();
+
+        let (groups, hosts): (i64, i64) = conn
+            .query_row("SELECT group_count, host_count FROM trash WHERE id = ?", [group_entry], |r| {
+                Ok((r.get(0)?, r.get(1)?))
+            })
+            .unwrap();
+        assert_eq!((groups, hosts), (2, 2));
+
+        let restored = restore_entry(&mut conn, group_entry).unwrap();
+        assert_eq!((restored.restored_groups, restored.restored_hosts), (2, 2));
+        assert!(!restored.moved_to_top_level);
+        // the host keeps its own entry
+        assert_eq!(live_hosts(&conn), 2);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM trash"), 1);
+
+        let restored = restore_entry(&mut conn, host_entry).unwrap();
+        assert!(!restored.moved_to_top_level);
+        let group: Option<i64> = conn
+            .query_row("SELECT group_id FROM hosts WHERE id = 2", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(group, Some(2));
+        assert_eq!(live_hosts(&conn), 3);
+        assert!(restore_entry(&mut conn, host_entry).is_err());
+    }
+
+    #[test]
+    fn restore_with_parent_in_trash_moves_to_top_level() {
+        let mut conn = fixture();
+        let host_entry = trash_host(&conn, 1).unwrap();
+        let sub_entry = trash_group(&conn, 2).unwrap();
+        trash_group(&conn, 1).unwrap();
+
+        let restored = restore_entry(&mut conn, sub_entry).unwrap();
+        assert!(restored.moved_to_top_level);
+        assert_eq!((restored.restored_groups, restored.restored_hosts), (1, 2));
+        let parent: Option<i64> = conn
+            .query_row("SELECT parent_id FROM groups WHERE id = 2", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(parent, None);
+
+        let restored = restore_entry(&mut conn, host_entry).unwrap();
+        assert!(restored.moved_to_top_level);
+        let group: Option<i64> = conn
+            .query_row("SELECT group_id FROM hosts WHERE id = 1", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(group, None);
+        assert_eq!(live_hosts(&conn), 3);
+    }
+
+    #[test]
+    fn purging_a_group_removes_entries_trashed_below_it() {
+        let conn = fixture();
+        conn.execute_batch(
+            "INSERT INTO groups (id, name) VALUES (3, 'Lab');
+             INSERT INTO hosts (id, name, host, port, username, auth_type, group_id)
+             VALUES (4, 'lab1', 'lab1.lan', 22, 'ops', 'key', 3);",
+        )
+        .unwrap();
+        trash_host(&conn, 2).unwrap();
+        trash_group(&conn, 2).unwrap();
+        let top_entry = trash_group(&conn, 1).unwrap();
+        let other = trash_host(&conn, 4).unwrap();
+
+        let purged = purge_entries(&conn, "id = ?1", rusqlite::params![top_entry]).unwrap();
+        assert_eq!(purged, 1);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM groups WHERE id IN (1, 2)"), 0);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM hosts WHERE id IN (1, 2, 3)"), 0);
+
+        // the DB and db1 entries lost their rows with the cascade
+        let left: Vec<i64> = {
+            let mut stmt = conn.prepare("SELECT id FROM trash ORDER BY id").unwrap();
+            let rows = stmt.query_map([], |r| r.get(0)).unwrap();
+            rows.map(Result::unwrap).collect()
+        };
+        assert_eq!(left, vec![other]);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM groups WHERE id = 3"), 1);
+    }
+
+    #[test]
+    fn purge_expired_honors_retention() {
+        let mut conn = fixture();
+        let old = trash_host(&conn, 1).unwrap();
+        let recent = trash_host(&conn, 2).unwrap();
+        conn.execute(
+            "UPDATE trash SET deleted_at = datetime('now', '-31 days') WHERE id = ?",
+            [old],
+        )
+        .unwrap();
+        conn.execute(
+            "UPDATE trash SET deleted_at = datetime('now', '-29 days') WHERE id = ?",
+            [recent],
+        )
+        .unwrap();
+
+        assert_eq!(purge_expired(&mut conn, &TrashPolicy { retention_days: 0 }).unwrap(), 0);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM trash"), 2);
+
+        assert_eq!(purge_expired(&mut conn, &TrashPolicy::default()).unwrap(), 1);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM hosts WHERE id = 1"), 0);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM hosts WHERE id = 2"), 1);
+
+        assert_eq!(purge_expired(&mut conn, &TrashPolicy { retention_days: 7 }).unwrap(), 1);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM trash"), 0);
+        assert_eq!(live_hosts(&conn), 1);
+    }
+}
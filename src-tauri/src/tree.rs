@@ -0,0 +1,687 @@
+use rusqlite::Connection;
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::commands::Host;
+use crate::db::Db;
+use crate::search::Breadcrumb;
+use crate::validation::{ensure_group, validate_name, CrudError, CrudErrorCode};
+
+/* =========================
+   CONFIG
+========================= */
+
+/// Hard stop for the recursive queries, in case the tree contains a cycle
+const MAX_TREE_DEPTH: i64 = 256;
+
+/* =========================
+   MODELS
+========================= */
+
+#[derive(Serialize)]
+pub struct TreeNode {
+    pub id: i64,
+    pub name: String,
+    pub parent_id: Option<i64>,
+    /// 0 for the top level of the returned tree
+    pub depth: i64,
+    pub direct_host_count: i64,
+    /// Hosts in this group and every sub group, including ones beyond `depth`
+    pub host_count: i64,
+    /// True when the group has sub groups, even if they were not loaded
+    pub has_children: bool,
+    pub favorite: bool,
+    pub children: Vec<TreeNode>,
+    /// Only filled with `include_hosts`
+    pub hosts: Vec<Host>,
+}
+
+#[derive(Serialize)]
+pub struct GroupTree {
+    /// `None` for the whole inventory
+    pub root_id: Option<i64>,
+    pub groups: Vec<TreeNode>,
+    /// Hosts directly in the root (ungrouped hosts for the whole inventory)
+    pub hosts: Vec<Host>,
+    pub direct_host_count: i64,
+    pub host_count: i64,
+}
+
+/* =========================
+   TAURI COMMANDS
+========================= */
+
+/// Nested group hierarchy below `root_id` in a single call. With `depth` only
+/// that many levels are loaded; deeper levels can be fetched later by calling
+/// again with the node's id as `root_id`.
+#[tauri::command]
+pub fn get_tree(
+    root_id: Option<i64>,
+    depth: Option<i64>,
+    include_hosts: Option<bool>,
+    db: tauri::State<Db>,
+) -> Result<GroupTree, String> {
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    let depth = depth.unwrap_or(MAX_TREE_DEPTH).clamp(1, MAX_TREE_DEPTH);
+
+    // an unknown or trashed root would otherwise look like an empty group
+    if let Some(id) = root_id {
+        ensure_group(&conn, id, "root_id")?;
+    }
+
+    let rows = load_tree_rows(&conn, root_id, depth)?;
+
+    let mut hosts_by_group = if include_hosts.unwrap_or(false) {
+        let mut group_ids: Vec<Option<i64>> = rows.iter().map(|r| Some(r.id)).collect();
+        group_ids.push(root_id);
+        load_hosts(&conn, &group_ids)?
+    } else {
+        HashMap::new()
+    };
+
+    let (direct_host_count, host_count) = match root_id {
+        Some(id) => conn
+            .query_row(
+                "WITH RECURSIVE sub(id, lvl) AS (
+                     SELECT ?1, 0
+                     UNION
+                     SELECT g.id, s.lvl + 1 FROM groups g JOIN sub s ON g.parent_id = s.id
+                     WHERE s.lvl < ?2 AND g.deleted_at IS NULL
+                 )
+                 SELECT (SELECT COUNT(*) FROM hosts WHERE group_id = ?1 AND deleted_at IS NULL),
+                        (SELECT COUNT(*) FROM hosts
+                         WHERE group_id IN (SELECT id FROM sub) AND deleted_at IS NULL)",
+                rusqlite::params![id, MAX_TREE_DEPTH],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .map_err(|e| e.to_string())?,
+        None => conn
+            .query_row(
+                "SELECT (SELECT COUNT(*) FROM hosts WHERE group_id IS NULL AND deleted_at IS NULL),
+                        (SELECT COUNT(*) FROM hosts WHERE deleted_at IS NULL)",
+                [],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .map_err(|e| e.to_string())?,
+    };
+
+    let mut children: HashMap<Option<i64>, Vec<TreeRow>> = HashMap::new();
+    for row in rows {
+        children.entry(row.parent_id).or_default().push(row);
+    }
+
+    Ok(GroupTree {
+        root_id,
+        groups: build_nodes(root_id, &mut children, &mut hosts_by_group),
+        hosts: hosts_by_group.remove(&root_id).unwrap_or_default(),
+        direct_host_count,
+        host_count,
+    })
+}
+
+/// Groups from the top level down to `group_id`, inclusive
+#[tauri::command]
+pub fn get_group_path(group_id: i64, db: tauri::State<Db>) -> Result<Vec<Breadcrumb>, String> {
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    group_path(&conn, group_id)
+}
+
+pub fn group_path(conn: &Connection, group_id: i64) -> Result<Vec<Breadcrumb>, String> {
+    let mut stmt = conn
+        .prepare(
+            "WITH RECURSIVE up(id, name, parent_id, lvl) AS (
+                 SELECT id, name, parent_id, 0 FROM groups WHERE id = ?1
+                 UNION ALL
+                 SELECT g.id, g.name, g.parent_id, u.lvl + 1 FROM groups g JOIN up u ON g.id = u.parent_id
+                 WHERE u.lvl < ?2
+             )
+             SELECT id, name FROM up ORDER BY lvl DESC",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let path: Vec<Breadcrumb> = stmt
+        .query_map(rusqlite::params![group_id, MAX_TREE_DEPTH], |r| {
+            Ok(Breadcrumb {
+                id: r.get(0)?,
+                name: r.get(1)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+
+    if path.is_empty() {
+        return Err("Group not found".into());
+    }
+    Ok(path)
+}
+
+/* =========================
+   MOVE / COPY
+========================= */
+
+/// `parent_id = None` moves the group to the top level
+#[tauri::command]
+pub fn move_group(id: i64, parent_id: Option<i64>, db: tauri::State<Db>) -> Result<(), CrudError> {
+    let conn = db.conn.lock().map_err(|_| CrudError::internal("DB lock failed"))?;
+    set_group_parent(&conn, id, parent_id)
+}
+
+fn set_group_parent(conn: &Connection, id: i64, parent_id: Option<i64>) -> Result<(), CrudError> {
+    ensure_group(conn, id, "id")?;
+    ensure_valid_parent(conn, id, parent_id)?;
+
+    conn.execute(
+        "UPDATE groups SET parent_id = ? WHERE id = ?",
+        rusqlite::params![parent_id, id],
+    )?;
+
+    Ok(())
+}
+
+/// Returns the number of hosts moved
+#[tauri::command]
+pub fn move_hosts(host_ids: Vec<i64>, group_id: Option<i64>, db: tauri::State<Db>) -> Result<usize, CrudError> {
+    let mut conn = db.conn.lock().map_err(|_| CrudError::internal("DB lock failed"))?;
+    if let Some(group_id) = group_id {
+        ensure_group(&conn, group_id, "group_id")?;
+    }
+
+    let tx = conn.transaction()?;
+    let mut moved = 0;
+    for host_id in &host_ids {
+        moved += tx.execute(
+            "UPDATE hosts SET group_id = ? WHERE id = ? AND deleted_at IS NULL",
+            rusqlite::params![group_id, host_id],
+        )?;
+    }
+    tx.commit()?;
+
+    Ok(moved)
+}
+
+/// Deep copy of the group, its sub groups, their hosts, tags, notes and fields below
+/// `parent_id`. Copying next to the original appends " (copy)" to the name.
+/// Returns the id of the new top group.
+#[tauri::command]
+pub fn copy_group(
+    id: i64,
+    parent_id: Option<i64>,
+    name: Option<String>,
+    db: tauri::State<Db>,
+) -> Result<i64, CrudError> {
+    let mut conn = db.conn.lock().map_err(|_| CrudError::internal("DB lock failed"))?;
+    copy_subtree(&mut conn, id, parent_id, name)
+}
+
+fn copy_subtree(
+    conn: &mut Connection,
+    id: i64,
+    parent_id: Option<i64>,
+    name: Option<String>,
+) -> Result<i64, CrudError> {
+    ensure_group(conn, id, "id")?;
+    if let Some(parent_id) = parent_id {
+        ensure_group(conn, parent_id, "parent_id")?;
+    }
+
+    // the subtree is read before copying, so copying into itself terminates
+    let subtree = load_subtree(conn, id).map_err(CrudError::internal)?;
+    let (source_name, source_parent): (String, Option<i64>) =
+        conn.query_row("SELECT name, parent_id FROM groups WHERE id = ?", [id], |r| {
+            Ok((r.get(0)?, r.get(1)?))
+        })?;
+    let top_name = match name.filter(|n| !n.trim().is_empty()) {
+        Some(n) => validate_name("name", &n)?,
+        None if source_parent == parent_id => format!("{} (copy)", source_name),
+        None => source_name,
+    };
+
+    let tx = conn.transaction()?;
+    let mut new_ids: HashMap<i64, i64> = HashMap::new();
+
+    for (group_id, group_name, group_parent) in &subtree {
+        let (name, parent) = if *group_id == id {
+            (top_name.clone(), parent_id)
+        } else {
+            (group_name.clone(), group_parent.and_then(|p| new_ids.get(&p).copied()))
+        };
+
+        // sub groups keep their order, the top group goes last in its new parent
+        tx.execute(
+            "INSERT INTO groups (name, parent_id, notes, sort_order)
+             SELECT ?1, ?2, notes, CASE WHEN ?3 THEN 0 ELSE sort_order END FROM groups WHERE id = ?4",
+            rusqlite::params![name, parent, *group_id == id, group_id],
+        )?;
+        let new_group = tx.last_insert_rowid();
+
+        tx.execute(
+            "INSERT INTO group_fields (group_id, key, value, position)
+             SELECT ?1, key, value, position FROM group_fields WHERE group_id = ?2",
+            rusqlite::params![new_group, group_id],
+        )?;
+        new_ids.insert(*group_id, new_group);
+
+        copy_group_hosts(&tx, *group_id, new_group)?;
+    }
+
+    tx.commit()?;
+    Ok(new_ids[&id])
+}
+
+/// Rejects a parent that is the group itself or one of its descendants,
+/// which would detach the subtree from the tree
+pub fn ensure_valid_parent(conn: &Connection, id: i64, parent_id: Option<i64>) -> Result<(), CrudError> {
+    let Some(parent_id) = parent_id else {
+        return Ok(());
+    };
+    if parent_id == id {
+        return Err(CrudError::field(
+            CrudErrorCode::InvalidParent,
+            "parent_id",
+            "A group cannot be its own parent",
+        ));
+    }
+    ensure_group(conn, parent_id, "parent_id")?;
+
+    let is_descendant = load_subtree(conn, id)
+        .map_err(CrudError::internal)?
+        .iter()
+        .any(|(group_id, _, _)| *group_id == parent_id);
+    if is_descendant {
+        return Err(CrudError::field(
+            CrudErrorCode::InvalidParent,
+            "parent_id",
+            "A group cannot be moved into one of its own sub groups",
+        ));
+    }
+    Ok(())
+}
+
+/// The group and all its descendants, parents before children
+fn load_subtree(conn: &Connection, id: i64) -> Result<Vec<(i64, String, Option<i64>)>, String> {
+    let mut stmt = conn
+        .prepare(
+            "WITH RECURSIVE sub(id, name, parent_id, lvl) AS (
+                 SELECT id, name, parent_id, 0 FROM groups WHERE id = ?1
+                 UNION
+                 SELECT g.id, g.name, g.parent_id, s.lvl + 1
+                 FROM groups g JOIN sub s ON g.parent_id = s.id
+                 WHERE s.lvl < ?2 AND g.deleted_at IS NULL
+             )
+             SELECT id, name, parent_id, MIN(lvl) FROM sub GROUP BY id ORDER BY MIN(lvl), id",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map(rusqlite::params![id, MAX_TREE_DEPTH], |r| {
+            Ok((r.get(0)?, r.get(1)?, r.get(2)?))
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.filter_map(Result::ok).collect())
+}
+
+fn copy_group_hosts(conn: &Connection, from_group: i64, to_group: i64) -> Result<(), CrudError> {
+    let host_ids: Vec<i64> = {
+        let mut stmt =
+            conn.prepare("SELECT id FROM hosts WHERE group_id = ? AND deleted_at IS NULL ORDER BY id")?;
+        let rows = stmt.query_map([from_group], |r| r.get(0))?;
+        rows.filter_map(Result::ok).collect()
+    };
+
+    for host_id in host_ids {
+        conn.execute(
+            "INSERT INTO hosts (name, host, port, username, password, auth_type, identity_file, proxy_jump, notes, sort_order, group_id)
+             SELECT name, host, port, username, password, auth_type, identity_file, proxy_jump, notes, sort_order, ?1
+             FROM hosts WHERE id = ?2",
+            rusqlite::params![to_group, host_id],
+        )?;
+        let new_host = conn.last_insert_rowid();
+
+        conn.execute(
+            "INSERT INTO host_tags (host_id, tag_id) SELECT ?1, tag_id FROM host_tags WHERE host_id = ?2",
+            rusqlite::params![new_host, host_id],
+        )?;
+        conn.execute(
+            "INSERT INTO host_fields (host_id, key, value, position)
+             SELECT ?1, key, value, position FROM host_fields WHERE host_id = ?2",
+            rusqlite::params![new_host, host_id],
+        )?;
+    }
+
+    Ok(())
+}
+
+/* =========================
+   ORDER
+========================= */
+
+/// `ids` lists sibling groups below `parent_id` in their new order; siblings
+/// left out keep their relative order after the listed ones
+#[tauri::command]
+pub fn reorder_groups(parent_id: Option<i64>, ids: Vec<i64>, db: tauri::State<Db>) -> Result<(), String> {
+    let mut conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    reorder(&mut conn, "groups", "parent_id", parent_id, &ids)
+}
+
+/// Same as `reorder_groups` for the hosts directly in `group_id`
+#[tauri::command]
+pub fn reorder_hosts(group_id: Option<i64>, ids: Vec<i64>, db: tauri::State<Db>) -> Result<(), String> {
+    let mut conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    reorder(&mut conn, "hosts", "group_id", group_id, &ids)
+}
+
+fn reorder(
+    conn: &mut Connection,
+    table: &str,
+    parent_column: &str,
+    parent: Option<i64>,
+    ids: &[i64],
+) -> Result<(), String> {
+    let siblings: Vec<i64> = {
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT id FROM {} WHERE {} IS ? AND deleted_at IS NULL
+                 ORDER BY sort_order, name COLLATE NOCASE, id",
+                table, parent_column
+            ))
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([parent], |r| r.get(0))
+            .map_err(|e| e.to_string())?;
+        rows.filter_map(Result::ok).collect()
+    };
+
+    let mut order: Vec<i64> = Vec::with_capacity(siblings.len());
+    for id in ids {
+        if !siblings.contains(id) {
+            return Err(format!("Item {} is not in this group", id));
+        }
+        if !order.contains(id) {
+            order.push(*id);
+        }
+    }
+    order.extend(siblings.iter().filter(|id| !ids.contains(id)));
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    for (position, id) in order.iter().enumerate() {
+        tx.execute(
+            &format!("UPDATE {} SET sort_order = ? WHERE id = ?", table),
+            rusqlite::params![position as i64 + 1, id],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())
+}
+
+/* =========================
+   QUERIES
+========================= */
+
+struct TreeRow {
+    id: i64,
+    name: String,
+    parent_id: Option<i64>,
+    depth: i64,
+    direct_host_count: i64,
+    host_count: i64,
+    has_children: bool,
+    favorite: bool,
+}
+
+fn load_tree_rows(conn: &Connection, root_id: Option<i64>, depth: i64) -> Result<Vec<TreeRow>, String> {
+    // `tree` holds the loaded levels, `closure` pairs every loaded group with
+    // all of its descendants so recursive counts include unloaded levels
+    let mut stmt = conn
+        .prepare(
+            "WITH RECURSIVE
+             tree(id, name, parent_id, depth) AS (
+                 SELECT id, name, parent_id, 0 FROM groups WHERE parent_id IS ?1 AND deleted_at IS NULL
+                 UNION ALL
+                 SELECT g.id, g.name, g.parent_id, t.depth + 1
+                 FROM groups g JOIN tree t ON g.parent_id = t.id
+                 WHERE t.depth + 1 < ?2 AND g.deleted_at IS NULL
+             ),
+             closure(ancestor, descendant, lvl) AS (
+                 SELECT id, id, 0 FROM tree
+                 UNION
+                 SELECT c.ancestor, g.id, c.lvl + 1
+                 FROM groups g JOIN closure c ON g.parent_id = c.descendant
+                 WHERE c.lvl < ?3 AND g.deleted_at IS NULL
+             ),
+             direct(group_id, n) AS (
+                 SELECT group_id, COUNT(*) FROM hosts
+                 WHERE group_id IS NOT NULL AND deleted_at IS NULL GROUP BY group_id
+             )
+             SELECT t.id, t.name, t.parent_id, t.depth,
+                    COALESCE((SELECT n FROM direct WHERE group_id = t.id), 0),
+                    (SELECT COALESCE(SUM(d.n), 0) FROM
+                        (SELECT DISTINCT descendant FROM closure WHERE ancestor = t.id) c
+                        JOIN direct d ON d.group_id = c.descendant),
+                    EXISTS (SELECT 1 FROM groups WHERE parent_id = t.id AND deleted_at IS NULL),
+                    g.favorite
+             FROM tree t JOIN groups g ON g.id = t.id
+             ORDER BY t.depth, g.sort_order, t.name COLLATE NOCASE, t.id",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map(rusqlite::params![root_id, depth, MAX_TREE_DEPTH], |r| {
+            Ok(TreeRow {
+                id: r.get(0)?,
+                name: r.get(1)?,
+                parent_id: r.get(2)?,
+                depth: r.get(3)?,
+                direct_host_count: r.get(4)?,
+                host_count: r.get(5)?,
+                has_children: r.get(6)?,
+                favorite: r.get(7)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.filter_map(Result::ok).collect())
+}
+
+fn load_hosts(
+    conn: &Connection,
+    group_ids: &[Option<i64>],
+) -> Result<HashMap<Option<i64>, Vec<Host>>, String> {
+    let ids: Vec<i64> = group_ids.iter().flatten().copied().collect();
+    let include_root = group_ids.contains(&None);
+
+    let sql = format!(
+        "SELECT id, name, host, port, username, auth_type, group_id, favorite
+         FROM hosts WHERE deleted_at IS NULL AND (group_id IN ({}) {})
+         ORDER BY sort_order, name COLLATE NOCASE, id",
+        vec!["?"; ids.len()].join(", "),
+        if include_root { "OR group_id IS NULL" } else { "" }
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map(rusqlite::params_from_iter(ids), |r| {
+            Ok(Host {
+                id: r.get(0)?,
+                name: r.get(1)?,
+                host: r.get(2)?,
+                port: r.get(3)?,
+                username: r.get(4)?,
+                auth_type: r.get(5)?,
+                group_id: r.get(6)?,
+                favorite: r.get(7)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut by_group: HashMap<Option<i64>, Vec<Host>> = HashMap::new();
+    for host in rows.filter_map(Result::ok) {
+        by_group.entry(host.group_id).or_default().push(host);
+    }
+    Ok(by_group)
+}
+
+fn build_nodes(
+    parent: Option<i64>,
+    children: &mut HashMap<Option<i64>, Vec<TreeRow>>,
+    hosts: &mut HashMap<Option<i64>, Vec<Host>>,
+) -> Vec<TreeNode> {
+    children
+        .remove(&parent)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|row| TreeNode {
+            children: build_nodes(Some(row.id), children, hosts),
+            hosts: hosts.remove(&Some(row.id)).unwrap_or_default(),
+            id: row.id,
+            name: row.name,
+            parent_id: row.parent_id,
+            depth: row.depth,
+            direct_host_count: row.direct_host_count,
+            host_count: row.host_count,
+            has_children: row.has_children,
+            favorite: row.favorite,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    /// 1 Prod > 2 DB > 3 Replicas, 4 Lab; host 1 `db1` in DB with a tag,
+    /// a field and notes, host 2 `replica1` in Replicas
+    fn fixture() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        crate::migrations::run_migrations(&mut conn, Path::new("/tmp")).unwrap();
+        conn.execute_batch(
+            "INSERT INTO groups (id, name, parent_id, notes) VALUES
+                 (1, 'Prod', NULL, NULL), (2, 'DB', 1, 'primary site'), (3, 'Replicas', 2, NULL), (4, 'Lab', NULL, NULL);
+             INSERT INTO group_fields (group_id, key, value) VALUES (2, 'owner', 'dba');
+             INSERT INTO hosts (id, name, host, port, username, auth_type, group_id, notes) VALUES
+                 (1, 'db1', '10.0.0.1', 22, 'ops', 'key', 2, 'runs postgres'),
+                 (2, 'replica1', '10.0.0.2', 22, 'ops', 'key', 3, NULL);
+             INSERT INTO tags (id, name) VALUES (1, 'role:db');
+             INSERT INTO host_tags (host_id, tag_id) VALUES (1, 1), (2, 1);
+             INSERT INTO host_fields (host_id, key, value) VALUES (1, 'rack', 'A3');",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn parent_of(conn: &Connection, id: i64) -> Option<i64> {
+        conn.query_row("SELECT parent_id FROM groups WHERE id = ?", [id], |r| r.get(0))
+            .unwrap()
+    }
+
+    fn child(conn: &Connection, parent_id: i64, name: &str) -> i64 {
+        conn.query_row(
+            "SELECT id FROM groups WHERE parent_id = ? AND name = ?",
+            rusqlite::params![parent_id, name],
+            |r| r.get(0),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn move_rejects_self_and_descendants() {
+        let conn = fixture();
+
+        for target in [2, 3] {
+            let err = set_group_parent(&conn, 2, Some(target)).unwrap_err();
+            assert_eq!((err.code, err.field), (CrudErrorCode::InvalidParent, Some("parent_id")));
+        }
+        let err = set_group_parent(&conn, 1, Some(3)).unwrap_err();
+        assert_eq!(err.code, CrudErrorCode::InvalidParent);
+        assert_eq!(parent_of(&conn, 2), Some(1));
+
+        let err = set_group_parent(&conn, 2, Some(99)).unwrap_err();
+        assert_eq!(err.code, CrudErrorCode::GroupNotFound);
+
+        set_group_parent(&conn, 2, Some(4)).unwrap();
+        assert_eq!(parent_of(&conn, 2), Some(4));
+        set_group_parent(&conn, 2, None).unwrap();
+        assert_eq!(parent_of(&conn, 2), None);
+    }
+
+    #[test]
+    fn update_group_rejects_descendant_parent() {
+        let conn = fixture();
+
+        let err = crate::commands::update_group_row(&conn, 1, "Prod", Some(3)).unwrap_err();
+        assert_eq!(err.code, CrudErrorCode::InvalidParent);
+        let name: String = conn
+            .query_row("SELECT name FROM groups WHERE id = 1", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(name, "Prod");
+        assert_eq!(parent_of(&conn, 1), None);
+
+        crate::commands::update_group_row(&conn, 3, " Standby ", Some(1)).unwrap();
+        assert_eq!(parent_of(&conn, 3), Some(1));
+    }
+
+    #[test]
+    fn copy_into_own_subtree_terminates() {
+        let mut conn = fixture();
+
+        let copy = copy_subtree(&mut conn, 2, Some(3), None).unwrap();
+        assert_eq!(parent_of(&conn, copy), Some(3));
+        let replicas = child(&conn, copy, "Replicas");
+        // the copy holds the subtree as it was before copying
+        let nested: i64 = conn
+            .query_row("SELECT COUNT(*) FROM groups WHERE parent_id = ?", [replicas], |r| r.get(0))
+            .unwrap();
+        assert_eq!(nested, 0);
+
+        let (notes, field): (Option<String>, String) = conn
+            .query_row(
+                "SELECT g.notes, f.value FROM groups g JOIN group_fields f ON f.group_id = g.id WHERE g.id = ?",
+                [copy],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((notes.as_deref(), field.as_str()), (Some("primary site"), "dba"));
+
+        let (host, notes, rack, tags): (i64, Option<String>, String, i64) = conn
+            .query_row(
+                "SELECT h.id, h.notes, f.value, (SELECT COUNT(*) FROM host_tags WHERE host_id = h.id)
+                 FROM hosts h JOIN host_fields f ON f.host_id = h.id
+                 WHERE h.group_id = ? AND h.name = 'db1'",
+                [copy],
+                |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)),
+            )
+            .unwrap();
+        assert_ne!(host, 1);
+        assert_eq!((notes.as_deref(), rack.as_str(), tags), (Some("runs postgres"), "A3", 1));
+
+        let replica_hosts: i64 = conn
+            .query_row("SELECT COUNT(*) FROM hosts WHERE group_id = ?", [replicas], |r| r.get(0))
+            .unwrap();
+        assert_eq!(replica_hosts, 1);
+        let total_hosts: i64 = conn.query_row("SELECT COUNT(*) FROM hosts", [], |r| r.get(0)).unwrap();
+        assert_eq!(total_hosts, 4);
+    }
+
+    #[test]
+    fn copy_names() {
+        let mut conn = fixture();
+        let name = |conn: &Connection, id: i64| -> String {
+            conn.query_row("SELECT name FROM groups WHERE id = ?", [id], |r| r.get(0))
+                .unwrap()
+        };
+
+        let beside = copy_subtree(&mut conn, 2, Some(1), None).unwrap();
+        assert_eq!(name(&conn, beside), "DB (copy)");
+        let elsewhere = copy_subtree(&mut conn, 2, Some(4), None).unwrap();
+        assert_eq!(name(&conn, elsewhere), "DB");
+        let top = copy_subtree(&mut conn, 4, None, Some("  ".into())).unwrap();
+        assert_eq!(name(&conn, top), "Lab (copy)");
+        let named = copy_subtree(&mut conn, 2, Some(1), Some(" Standby ".into())).unwrap();
+        assert_eq!(name(&conn, named), "Standby");
+
+        let err = copy_subtree(&mut conn, 2, Some(99), None).unwrap_err();
+        assert_eq!((err.code, err.field), (CrudErrorCode::GroupNotFound, Some("parent_id")));
+    }
+}
@@ -0,0 +1,829 @@
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use ssh2::{Channel, ErrorCode, Session};
+use std::{
+    collections::HashMap,
+    io::{self, Read, Write},
+    net::{Shutdown, TcpListener, TcpStream, ToSocketAddrs},
+    sync::{
+        atomic::{AtomicBool, AtomicU16, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+use tauri::{AppHandle, Emitter};
+
+use crate::ssh_session::connect_host;
+
+/* =========================
+   CONFIG
+========================= */
+
+const DEFAULT_BIND_HOST: &str = "127.0.0.1";
+const BUFFER_SIZE: usize = 32 * 1024;
+const IDLE_SLEEP_MS: u64 = 5;
+const ACCEPT_SLEEP_MS: u64 = 50;
+const CHANNEL_OPEN_TIMEOUT_SECS: u64 = 15;
+const TARGET_CONNECT_TIMEOUT_SECS: u64 = 10;
+const SOCKS_HANDSHAKE_TIMEOUT_SECS: u64 = 10;
+const KEEPALIVE_SECS: u32 = 30;
+const RESTART_MIN_SECS: u64 = 5;
+const RESTART_MAX_SECS: u64 = 120;
+/// libssh2 LIBSSH2_ERROR_EAGAIN, returned by a non-blocking session that would block
+const LIBSSH2_EAGAIN: i32 = -37;
+
+/* =========================
+   REGISTRY
+========================= */
+
+static TUNNELS: Lazy<Mutex<HashMap<String, Arc<Tunnel>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+static TUNNEL_SEQ: AtomicU64 = AtomicU64::new(1);
+
+/* =========================
+   MODELS
+========================= */
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TunnelKind {
+    /// ssh -L: local listener, connections forwarded to target via the host
+    Local,
+    /// ssh -R: listener on the host, connections forwarded to a local target
+    Remote,
+    /// ssh -D: local SOCKS5 proxy, each CONNECT is opened from the host
+    Dynamic,
+}
+
+impl TunnelKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TunnelKind::Local => "local",
+            TunnelKind::Remote => "remote",
+            TunnelKind::Dynamic => "dynamic",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "local" => Some(TunnelKind::Local),
+            "remote" => Some(TunnelKind::Remote),
+            "dynamic" => Some(TunnelKind::Dynamic),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TunnelStatus {
+    Starting,
+    Running,
+    Stopped,
+    Failed,
+}
+
+/// For `Local` the bind address is on this machine and the target is reached from the host;
+/// for `Remote` the bind address is on the host and the target is reached from this machine.
+/// `Dynamic` tunnels take their target from each SOCKS request and leave it empty.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TunnelSpec {
+    pub kind: TunnelKind,
+    pub host_id: i64,
+    pub bind_host: String,
+    pub bind_port: u16,
+    pub target_host: String,
+    pub target_port: u16,
+}
+
+#[derive(Serialize, Clone)]
+pub struct TunnelInfo {
+    pub tunnel_id: String,
+    #[serde(flatten)]
+    pub spec: TunnelSpec,
+    pub profile_id: Option<i64>,
+    pub status: TunnelStatus,
+    pub error: Option<String>,
+    /// Port actually listening, differs from `bind_port` when a remote tunnel
+    /// asked the server to pick one with port 0
+    pub bound_port: Option<u16>,
+    pub connections_active: u64,
+    pub connections_total: u64,
+    /// bytes received from the forwarded peer and sent into the tunnel
+    pub bytes_in: u64,
+    /// bytes received from the tunnel and sent back to the peer
+    pub bytes_out: u64,
+}
+
+#[derive(Default)]
+struct TunnelStats {
+    connections_active: AtomicU64,
+    connections_total: AtomicU64,
+    bytes_in: AtomicU64,
+    bytes_out: AtomicU64,
+}
+
+struct Tunnel {
+    tunnel_id: String,
+    spec: TunnelSpec,
+    profile_id: Option<i64>,
+    restart_on_failure: bool,
+    state: Mutex<(TunnelStatus, Option<String>)>,
+    stats: TunnelStats,
+    /// 0 until the listener is up
+    bound_port: AtomicU16,
+    stop: AtomicBool,
+}
+
+impl Tunnel {
+    fn snapshot(&self) -> TunnelInfo {
+        let (status, error) = self.state.lock().unwrap().clone();
+        TunnelInfo {
+            tunnel_id: self.tunnel_id.clone(),
+            spec: self.spec.clone(),
+            profile_id: self.profile_id,
+            status,
+            error,
+            bound_port: Some(self.bound_port.load(Ordering::Relaxed)).filter(|p| *p != 0),
+            connections_active: self.stats.connections_active.load(Ordering::Relaxed),
+            connections_total: self.stats.connections_total.load(Ordering::Relaxed),
+            bytes_in: self.stats.bytes_in.load(Ordering::Relaxed),
+            bytes_out: self.stats.bytes_out.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/* =========================
+   EVENTS
+========================= */
+
+#[derive(Serialize, Clone)]
+struct TunnelStatusEvent {
+    tunnel_id: String,
+    status: TunnelStatus,
+    error: Option<String>,
+    bound_port: Option<u16>,
+}
+
+#[derive(Serialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+enum ConnectionState {
+    Opened,
+    Closed,
+}
+
+#[derive(Serialize, Clone)]
+struct TunnelConnectionEvent {
+    tunnel_id: String,
+    state: ConnectionState,
+    /// Client address when known; SSH does not report the originator of remote forwards
+    peer: Option<String>,
+    connections_active: u64,
+}
+
+/* =========================
+   TAURI COMMANDS
+========================= */
+
+#[tauri::command]
+pub fn tunnel_start_local(
+    host_id: i64,
+    bind_host: Option<String>,
+    bind_port: u16,
+    target_host: String,
+    target_port: u16,
+    app: AppHandle,
+) -> Result<TunnelInfo, String> {
+    start_tunnel(
+        &app,
+        TunnelSpec {
+            kind: TunnelKind::Local,
+            host_id,
+            bind_host: bind_host.unwrap_or_else(|| DEFAULT_BIND_HOST.into()),
+            bind_port,
+            target_host,
+            target_port,
+        },
+        None,
+        false,
+    )
+}
+
+#[tauri::command]
+pub fn tunnel_start_remote(
+    host_id: i64,
+    bind_host: Option<String>,
+    bind_port: u16,
+    target_host: String,
+    target_port: u16,
+    app: AppHandle,
+) -> Result<TunnelInfo, String> {
+    start_tunnel(
+        &app,
+        TunnelSpec {
+            kind: TunnelKind::Remote,
+            host_id,
+            bind_host: bind_host.unwrap_or_else(|| DEFAULT_BIND_HOST.into()),
+            bind_port,
+            target_host,
+            target_port,
+        },
+        None,
+        false,
+    )
+}
+
+#[tauri::command]
+pub fn tunnel_start_dynamic(
+    host_id: i64,
+    bind_host: Option<String>,
+    bind_port: u16,
+    app: AppHandle,
+) -> Result<TunnelInfo, String> {
+    start_tunnel(
+        &app,
+        TunnelSpec {
+            kind: TunnelKind::Dynamic,
+            host_id,
+            bind_host: bind_host.unwrap_or_else(|| DEFAULT_BIND_HOST.into()),
+            bind_port,
+            target_host: String::new(),
+            target_port: 0,
+        },
+        None,
+        false,
+    )
+}
+
+#[tauri::command]
+pub fn tunnel_stop(tunnel_id: String, app: AppHandle) -> Result<(), String> {
+    let tunnel = TUNNELS
+        .lock()
+        .unwrap()
+        .remove(&tunnel_id)
+        .ok_or("Tunnel not found")?;
+
+    tunnel.stop.store(true, Ordering::Relaxed);
+    set_status(&app, &tunnel, TunnelStatus::Stopped, None);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn tunnel_list() -> Vec<TunnelInfo> {
+    let mut list: Vec<TunnelInfo> = TUNNELS
+        .lock()
+        .unwrap()
+        .values()
+        .map(|t| t.snapshot())
+        .collect();
+    list.sort_by(|a, b| a.tunnel_id.cmp(&b.tunnel_id));
+    list
+}
+
+/* =========================
+   LIFECYCLE
+========================= */
+
+/// Starts a tunnel. Profile-backed tunnels may ask to be restarted with backoff
+/// whenever the SSH connection or listener fails.
+pub fn start_tunnel(
+    app: &AppHandle,
+    spec: TunnelSpec,
+    profile_id: Option<i64>,
+    restart_on_failure: bool,
+) -> Result<TunnelInfo, String> {
+    // only the SSH server can pick a free port for us
+    if spec.bind_port == 0 && spec.kind != TunnelKind::Remote {
+        return Err("Bind port is required".into());
+    }
+
+    // bind before returning so "address in use" reaches the caller directly
+    let listener = bind_listener(&spec)?;
+
+    let tunnel_id = format!(
+        "tunnel-{}-{}",
+        chrono::Utc::now().timestamp_millis(),
+        TUNNEL_SEQ.fetch_add(1, Ordering::Relaxed)
+    );
+
+    // remote ports are known once the server accepted the forward
+    let bound_port = if listener.is_some() { spec.bind_port } else { 0 };
+
+    let tunnel = Arc::new(Tunnel {
+        tunnel_id: tunnel_id.clone(),
+        spec,
+        profile_id,
+        restart_on_failure,
+        state: Mutex::new((TunnelStatus::Starting, None)),
+        stats: TunnelStats::default(),
+        bound_port: AtomicU16::new(bound_port),
+        stop: AtomicBool::new(false),
+    });
+
+    {
+        let mut tunnels = TUNNELS.lock().unwrap();
+        // a profile started again replaces its tunnel that failed for good
+        if profile_id.is_some() {
+            tunnels.retain(|_, t| t.profile_id != profile_id || !t.stop.load(Ordering::Relaxed));
+        }
+        tunnels.insert(tunnel_id, tunnel.clone());
+    }
+
+    let app = app.clone();
+    let worker = tunnel.clone();
+    std::thread::spawn(move || supervise(&app, &worker, listener));
+
+    Ok(tunnel.snapshot())
+}
+
+/// Ids of profiles with a tunnel that is starting, running or waiting to
+/// restart. Tunnels that failed for good stay listed but do not count.
+pub fn active_profile_ids() -> Vec<i64> {
+    TUNNELS
+        .lock()
+        .unwrap()
+        .values()
+        .filter(|t| !t.stop.load(Ordering::Relaxed))
+        .filter_map(|t| t.profile_id)
+        .collect()
+}
+
+pub fn stop_profile_tunnels(app: &AppHandle, profile_id: i64) {
+    let stopped: Vec<Arc<Tunnel>> = {
+        let mut tunnels = TUNNELS.lock().unwrap();
+        let ids: Vec<String> = tunnels
+            .values()
+            .filter(|t| t.profile_id == Some(profile_id))
+            .map(|t| t.tunnel_id.clone())
+            .collect();
+        ids.iter().filter_map(|id| tunnels.remove(id)).collect()
+    };
+
+    for tunnel in stopped {
+        tunnel.stop.store(true, Ordering::Relaxed);
+        set_status(app, &tunnel, TunnelStatus::Stopped, None);
+    }
+}
+
+fn bind_listener(spec: &TunnelSpec) -> Result<Option<TcpListener>, String> {
+    match spec.kind {
+        TunnelKind::Local | TunnelKind::Dynamic => {
+            let l = TcpListener::bind((spec.bind_host.as_str(), spec.bind_port)).map_err(|e| {
+                format!("Cannot listen on {}:{}: {}", spec.bind_host, spec.bind_port, e)
+            })?;
+            l.set_nonblocking(true).map_err(|e| e.to_string())?;
+            Ok(Some(l))
+        }
+        TunnelKind::Remote => Ok(None),
+    }
+}
+
+fn supervise(app: &AppHandle, tunnel: &Arc<Tunnel>, first_listener: Option<TcpListener>) {
+    let mut listener = Some(first_listener);
+    let mut delay = RESTART_MIN_SECS;
+
+    loop {
+        let started = Instant::now();
+        let result = match listener.take() {
+            Some(l) => tunnel_worker(app, tunnel, l),
+            None => bind_listener(&tunnel.spec).and_then(|l| tunnel_worker(app, tunnel, l)),
+        };
+
+        if tunnel.stop.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let error = result.err().unwrap_or_else(|| "Tunnel closed".into());
+        println!("[Tunnel] {} failed: {}", tunnel.tunnel_id, error);
+        set_status(app, tunnel, TunnelStatus::Failed, Some(error));
+
+        if !tunnel.restart_on_failure {
+            tunnel.stop.store(true, Ordering::Relaxed);
+            return;
+        }
+
+        // a tunnel that stayed up for a while starts the backoff over
+        if started.elapsed() >= Duration::from_secs(RESTART_MAX_SECS) {
+            delay = RESTART_MIN_SECS;
+        }
+
+        let wake = Instant::now() + Duration::from_secs(delay);
+        while Instant::now() < wake {
+            if tunnel.stop.load(Ordering::Relaxed) {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(ACCEPT_SLEEP_MS));
+        }
+        delay = (delay * 2).min(RESTART_MAX_SECS);
+
+        println!("[Tunnel] {} restarting", tunnel.tunnel_id);
+        set_status(app, tunnel, TunnelStatus::Starting, None);
+    }
+}
+
+fn tunnel_worker(
+    app: &AppHandle,
+    tunnel: &Arc<Tunnel>,
+    listener: Option<TcpListener>,
+) -> Result<(), String> {
+    let sess = connect_host(app, tunnel.spec.host_id)?;
+    sess.set_keepalive(true, KEEPALIVE_SECS);
+    // every connection shares this session, so no call may block the others
+    sess.set_blocking(false);
+
+    match (tunnel.spec.kind, listener) {
+        (TunnelKind::Local | TunnelKind::Dynamic, Some(listener)) => {
+            mark_running(app, tunnel);
+            accept_local(app, &sess, tunnel, listener)
+        }
+        (TunnelKind::Remote, _) => accept_remote(app, &sess, tunnel),
+        (_, None) => Err("Local listener missing".into()),
+    }
+}
+
+fn mark_running(app: &AppHandle, tunnel: &Tunnel) {
+    if !tunnel.stop.load(Ordering::Relaxed) {
+        set_status(app, tunnel, TunnelStatus::Running, None);
+    }
+}
+
+fn accept_local(
+    app: &AppHandle,
+    sess: &Session,
+    tunnel: &Arc<Tunnel>,
+    listener: TcpListener,
+) -> Result<(), String> {
+    let mut next_keepalive = Instant::now();
+
+    while !tunnel.stop.load(Ordering::Relaxed) {
+        keepalive(sess, &mut next_keepalive)?;
+
+        let (tcp, peer) = match listener.accept() {
+            Ok(c) => c,
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                std::thread::sleep(Duration::from_millis(ACCEPT_SLEEP_MS));
+                continue;
+            }
+            Err(e) => return Err(e.to_string()),
+        };
+
+        let app = app.clone();
+        let sess = sess.clone();
+        let tunnel = tunnel.clone();
+        std::thread::spawn(move || {
+            let result = match tunnel.spec.kind {
+                TunnelKind::Dynamic => socks_connect(&sess, &tunnel, tcp),
+                _ => {
+                    let spec = &tunnel.spec;
+                    retry_eagain(&tunnel.stop, || {
+                        sess.channel_direct_tcpip(&spec.target_host, spec.target_port, None)
+                    })
+                    .map(|channel| (channel, tcp))
+                }
+            }
+            .and_then(|(channel, tcp)| {
+                forward_connection(&app, &tunnel, channel, tcp, Some(peer.to_string()))
+            });
+
+            if let Err(e) = result {
+                println!("[Tunnel] {} connection from {} failed: {}", tunnel.tunnel_id, peer, e);
+            }
+        });
+    }
+
+    Ok(())
+}
+
+fn accept_remote(app: &AppHandle, sess: &Session, tunnel: &Arc<Tunnel>) -> Result<(), String> {
+    let spec = &tunnel.spec;
+    let (mut listener, bound_port) = retry_eagain(&tunnel.stop, || {
+        sess.channel_forward_listen(spec.bind_port, Some(&spec.bind_host), None)
+    })
+    .map_err(|e| format!("Remote listen on {}:{} refused: {}", spec.bind_host, spec.bind_port, e))?;
+
+    println!("[Tunnel] {} listening on remote port {}", tunnel.tunnel_id, bound_port);
+    tunnel.bound_port.store(bound_port, Ordering::Relaxed);
+    mark_running(app, tunnel);
+
+    let mut next_keepalive = Instant::now();
+
+    // dropping the listener cancels the forward on the server
+    while !tunnel.stop.load(Ordering::Relaxed) {
+        keepalive(sess, &mut next_keepalive)?;
+
+        let channel = match listener.accept() {
+            Ok(c) => c,
+            Err(e) if e.code() == ErrorCode::Session(LIBSSH2_EAGAIN) => {
+                std::thread::sleep(Duration::from_millis(ACCEPT_SLEEP_MS));
+                continue;
+            }
+            Err(e) => return Err(e.to_string()),
+        };
+
+        let app = app.clone();
+        let tunnel = tunnel.clone();
+        std::thread::spawn(move || {
+            let result = connect_target(&tunnel.spec.target_host, tunnel.spec.target_port)
+                .and_then(|tcp| forward_connection(&app, &tunnel, channel, tcp, None));
+
+            if let Err(e) = result {
+                println!("[Tunnel] {} remote connection failed: {}", tunnel.tunnel_id, e);
+            }
+        });
+    }
+
+    Ok(())
+}
+
+/* =========================
+   SOCKS5 (RFC 1928)
+   Only CONNECT without authentication; domain names are resolved by the host
+========================= */
+
+const SOCKS_VERSION: u8 = 5;
+const SOCKS_NO_AUTH: u8 = 0x00;
+const SOCKS_NO_ACCEPTABLE_METHOD: u8 = 0xff;
+const SOCKS_CMD_CONNECT: u8 = 0x01;
+const SOCKS_ATYP_IPV4: u8 = 0x01;
+const SOCKS_ATYP_DOMAIN: u8 = 0x03;
+const SOCKS_ATYP_IPV6: u8 = 0x04;
+const SOCKS_REPLY_OK: u8 = 0x00;
+const SOCKS_REPLY_GENERAL_FAILURE: u8 = 0x01;
+const SOCKS_REPLY_CMD_NOT_SUPPORTED: u8 = 0x07;
+const SOCKS_REPLY_ATYP_NOT_SUPPORTED: u8 = 0x08;
+
+fn socks_connect(
+    sess: &Session,
+    tunnel: &Tunnel,
+    mut tcp: TcpStream,
+) -> Result<(Channel, TcpStream), String> {
+    // accepted sockets may inherit the listener's non-blocking mode
+    tcp.set_nonblocking(false).map_err(|e| e.to_string())?;
+    tcp.set_read_timeout(Some(Duration::from_secs(SOCKS_HANDSHAKE_TIMEOUT_SECS)))
+        .map_err(|e| e.to_string())?;
+
+    /* ===== GREETING ===== */
+    let mut head = [0u8; 2];
+    tcp.read_exact(&mut head).map_err(|e| e.to_string())?;
+    if head[0] != SOCKS_VERSION {
+        return Err(format!("Unsupported SOCKS version {}", head[0]));
+    }
+
+    let mut methods = vec![0u8; head[1] as usize];
+    tcp.read_exact(&mut methods).map_err(|e| e.to_string())?;
+    if !methods.contains(&SOCKS_NO_AUTH) {
+        tcp.write_all(&[SOCKS_VERSION, SOCKS_NO_ACCEPTABLE_METHOD]).ok();
+        return Err("SOCKS client requires authentication".into());
+    }
+    tcp.write_all(&[SOCKS_VERSION, SOCKS_NO_AUTH])
+        .map_err(|e| e.to_string())?;
+
+    /* ===== REQUEST ===== */
+    let mut req = [0u8; 4];
+    tcp.read_exact(&mut req).map_err(|e| e.to_string())?;
+    if req[1] != SOCKS_CMD_CONNECT {
+        socks_reply(&mut tcp, SOCKS_REPLY_CMD_NOT_SUPPORTED);
+        return Err(format!("Unsupported SOCKS command {}", req[1]));
+    }
+
+    let host = match req[3] {
+        SOCKS_ATYP_IPV4 => {
+            let mut ip = [0u8; 4];
+            tcp.read_exact(&mut ip).map_err(|e| e.to_string())?;
+            std::net::Ipv4Addr::from(ip).to_string()
+        }
+        SOCKS_ATYP_IPV6 => {
+            let mut ip = [0u8; 16];
+            tcp.read_exact(&mut ip).map_err(|e| e.to_string())?;
+            std::net::Ipv6Addr::from(ip).to_string()
+        }
+        SOCKS_ATYP_DOMAIN => {
+            let mut len = [0u8; 1];
+            tcp.read_exact(&mut len).map_err(|e| e.to_string())?;
+            let mut name = vec![0u8; len[0] as usize];
+            tcp.read_exact(&mut name).map_err(|e| e.to_string())?;
+            String::from_utf8(name).map_err(|_| "Invalid SOCKS domain name".to_string())?
+        }
+        other => {
+            socks_reply(&mut tcp, SOCKS_REPLY_ATYP_NOT_SUPPORTED);
+            return Err(format!("Unsupported SOCKS address type {}", other));
+        }
+    };
+
+    let mut port = [0u8; 2];
+    tcp.read_exact(&mut port).map_err(|e| e.to_string())?;
+    let port = u16::from_be_bytes(port);
+
+    /* ===== CONNECT THROUGH HOST ===== */
+    let channel = match retry_eagain(&tunnel.stop, || sess.channel_direct_tcpip(&host, port, None)) {
+        Ok(c) => c,
+        Err(e) => {
+            socks_reply(&mut tcp, SOCKS_REPLY_GENERAL_FAILURE);
+            return Err(format!("{}:{}: {}", host, port, e));
+        }
+    };
+
+    socks_reply(&mut tcp, SOCKS_REPLY_OK);
+    tcp.set_read_timeout(None).map_err(|e| e.to_string())?;
+
+    Ok((channel, tcp))
+}
+
+/// Replies with an all-zero IPv4 bound address; clients do not rely on it for CONNECT
+fn socks_reply(tcp: &mut TcpStream, code: u8) {
+    let reply = [
+        SOCKS_VERSION,
+        code,
+        0x00,
+        SOCKS_ATYP_IPV4,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+    ];
+    tcp.write_all(&reply).ok();
+}
+
+/* =========================
+   FORWARDING
+========================= */
+
+/// Copies data both ways between the TCP peer and the SSH channel until either side closes
+fn forward_connection(
+    app: &AppHandle,
+    tunnel: &Tunnel,
+    mut channel: Channel,
+    mut tcp: TcpStream,
+    peer: Option<String>,
+) -> Result<(), String> {
+    let stats = &tunnel.stats;
+    stats.connections_active.fetch_add(1, Ordering::Relaxed);
+    stats.connections_total.fetch_add(1, Ordering::Relaxed);
+    emit_connection(app, tunnel, ConnectionState::Opened, peer.clone());
+
+    let result = pump(tunnel, &mut channel, &mut tcp);
+
+    stats.connections_active.fetch_sub(1, Ordering::Relaxed);
+    tcp.shutdown(Shutdown::Both).ok();
+    retry_eagain(&tunnel.stop, || channel.close()).ok();
+    emit_connection(app, tunnel, ConnectionState::Closed, peer);
+
+    result
+}
+
+fn connect_target(host: &str, port: u16) -> Result<TcpStream, String> {
+    let addr = (host, port)
+        .to_socket_addrs()
+        .map_err(|e| format!("Cannot resolve {}: {}", host, e))?
+        .next()
+        .ok_or("Resolve failed")?;
+
+    TcpStream::connect_timeout(&addr, Duration::from_secs(TARGET_CONNECT_TIMEOUT_SECS))
+        .map_err(|e| format!("Cannot connect to {}:{}: {}", host, port, e))
+}
+
+fn pump(tunnel: &Tunnel, channel: &mut Channel, tcp: &mut TcpStream) -> Result<(), String> {
+    tcp.set_nonblocking(true).map_err(|e| e.to_string())?;
+
+    let stats = &tunnel.stats;
+    let mut buf = vec![0u8; BUFFER_SIZE];
+    let mut tcp_eof = false;
+
+    loop {
+        if tunnel.stop.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        let mut idle = true;
+
+        if !tcp_eof {
+            match tcp.read(&mut buf) {
+                Ok(0) => {
+                    tcp_eof = true;
+                    retry_eagain(&tunnel.stop, || channel.send_eof())?;
+                }
+                Ok(n) => {
+                    write_all_nonblocking(channel, &buf[..n], &tunnel.stop)?;
+                    stats.bytes_in.fetch_add(n as u64, Ordering::Relaxed);
+                    idle = false;
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+                Err(e) => return Err(e.to_string()),
+            }
+        }
+
+        match channel.read(&mut buf) {
+            Ok(0) => {
+                if channel.eof() {
+                    return Ok(());
+                }
+            }
+            Ok(n) => {
+                write_all_nonblocking(tcp, &buf[..n], &tunnel.stop)?;
+                stats.bytes_out.fetch_add(n as u64, Ordering::Relaxed);
+                idle = false;
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+            Err(e) => return Err(e.to_string()),
+        }
+
+        if idle {
+            std::thread::sleep(Duration::from_millis(IDLE_SLEEP_MS));
+        }
+    }
+}
+
+/* =========================
+   HELPERS
+========================= */
+
+fn write_all_nonblocking(w: &mut impl Write, mut data: &[u8], stop: &AtomicBool) -> Result<(), String> {
+    while !data.is_empty() {
+        if stop.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        match w.write(data) {
+            Ok(0) => return Err("Connection closed".into()),
+            Ok(n) => data = &data[n..],
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                std::thread::sleep(Duration::from_millis(IDLE_SLEEP_MS));
+            }
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+    Ok(())
+}
+
+/// Retries a libssh2 call on a non-blocking session until it stops returning EAGAIN
+fn retry_eagain<T>(
+    stop: &AtomicBool,
+    mut op: impl FnMut() -> Result<T, ssh2::Error>,
+) -> Result<T, String> {
+    let deadline = Instant::now() + Duration::from_secs(CHANNEL_OPEN_TIMEOUT_SECS);
+
+    loop {
+        match op() {
+            Ok(v) => return Ok(v),
+            Err(e) if e.code() == ErrorCode::Session(LIBSSH2_EAGAIN) => {
+                if stop.load(Ordering::Relaxed) {
+                    return Err("Tunnel stopped".into());
+                }
+                if Instant::now() >= deadline {
+                    return Err("SSH operation timed out".into());
+                }
+                std::thread::sleep(Duration::from_millis(IDLE_SLEEP_MS));
+            }
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+}
+
+/// Sends a keepalive when due; a dead connection surfaces here as an error
+fn keepalive(sess: &Session, next: &mut Instant) -> Result<(), String> {
+    if Instant::now() < *next {
+        return Ok(());
+    }
+
+    match sess.keepalive_send() {
+        Ok(secs) => {
+            *next = Instant::now() + Duration::from_secs(secs.max(1) as u64);
+            Ok(())
+        }
+        Err(e) if e.code() == ErrorCode::Session(LIBSSH2_EAGAIN) => Ok(()),
+        Err(e) => Err(format!("SSH connection lost: {}", e)),
+    }
+}
+
+fn set_status(app: &AppHandle, tunnel: &Tunnel, status: TunnelStatus, error: Option<String>) {
+    *tunnel.state.lock().unwrap() = (status, error.clone());
+
+    let _ = app.emit(
+        "tunnel:status",
+        TunnelStatusEvent {
+            tunnel_id: tunnel.tunnel_id.clone(),
+            status,
+            error,
+            bound_port: Some(tunnel.bound_port.load(Ordering::Relaxed)).filter(|p| *p != 0),
+        },
+    );
+}
+
+fn emit_connection(
+    app: &AppHandle,
+    tunnel: &Tunnel,
+    state: ConnectionState,
+    peer: Option<String>,
+) {
+    let _ = app.emit(
+        "tunnel:connection",
+        TunnelConnectionEvent {
+            tunnel_id: tunnel.tunnel_id.clone(),
+            state,
+            peer,
+            connections_active: tunnel.stats.connections_active.load(Ordering::Relaxed),
+        },
+    );
+}
@@ -0,0 +1,292 @@
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::db::Db;
+use crate::tunnel::{
+    active_profile_ids, start_tunnel, stop_profile_tunnels, TunnelInfo, TunnelKind, TunnelSpec,
+};
+
+/* =========================
+   MODELS
+========================= */
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TunnelAutoStart {
+    Manual,
+    AppLaunch,
+    /// Started when a terminal session to the owning host opens
+    HostSession,
+}
+
+impl TunnelAutoStart {
+    fn as_str(self) -> &'static str {
+        match self {
+            TunnelAutoStart::Manual => "manual",
+            TunnelAutoStart::AppLaunch => "app_launch",
+            TunnelAutoStart::HostSession => "host_session",
+        }
+    }
+
+    fn parse(s: &str) -> Self {
+        match s {
+            "app_launch" => TunnelAutoStart::AppLaunch,
+            "host_session" => TunnelAutoStart::HostSession,
+            _ => TunnelAutoStart::Manual,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct TunnelProfile {
+    pub id: i64,
+    pub name: String,
+    pub kind: TunnelKind,
+    pub host_id: i64,
+    pub bind_host: String,
+    pub bind_port: u16,
+    pub target_host: String,
+    pub target_port: u16,
+    pub auto_start: TunnelAutoStart,
+    pub restart_on_failure: bool,
+}
+
+#[derive(Deserialize)]
+pub struct TunnelProfileInput {
+    pub name: String,
+    pub kind: TunnelKind,
+    pub host_id: i64,
+    pub bind_host: Option<String>,
+    pub bind_port: u16,
+    #[serde(default)]
+    pub target_host: String,
+    #[serde(default)]
+    pub target_port: u16,
+    pub auto_start: TunnelAutoStart,
+    #[serde(default)]
+    pub restart_on_failure: bool,
+}
+
+impl TunnelProfile {
+    fn spec(&self) -> TunnelSpec {
+        TunnelSpec {
+            kind: self.kind,
+            host_id: self.host_id,
+            bind_host: self.bind_host.clone(),
+            bind_port: self.bind_port,
+            target_host: self.target_host.clone(),
+            target_port: self.target_port,
+        }
+    }
+}
+
+/* =========================
+   TAURI COMMANDS
+========================= */
+
+#[tauri::command]
+pub fn list_tunnel_profiles(
+    host_id: Option<i64>,
+    db: tauri::State<Db>,
+) -> Result<Vec<TunnelProfile>, String> {
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    load_profiles(&conn, "host_id = IFNULL(?1, host_id)", rusqlite::params![host_id])
+}
+
+#[tauri::command]
+pub fn create_tunnel_profile(
+    profile: TunnelProfileInput,
+    db: tauri::State<Db>,
+) -> Result<i64, String> {
+    validate(&profile)?;
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+
+    conn.execute(
+        "INSERT INTO tunnel_profiles
+         (name, kind, host_id, bind_host, bind_port, target_host, target_port, auto_start, restart_on_failure)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        rusqlite::params![
+            profile.name,
+            profile.kind.as_str(),
+            profile.host_id,
+            profile.bind_host.unwrap_or_else(|| "127.0.0.1".into()),
+            profile.bind_port,
+            profile.target_host,
+            profile.target_port,
+            profile.auto_start.as_str(),
+            profile.restart_on_failure,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(conn.last_insert_rowid())
+}
+
+/// Running tunnels keep their old settings until restarted
+#[tauri::command]
+pub fn update_tunnel_profile(
+    id: i64,
+    profile: TunnelProfileInput,
+    db: tauri::State<Db>,
+) -> Result<(), String> {
+    validate(&profile)?;
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+
+    conn.execute(
+        "UPDATE tunnel_profiles
+         SET name = ?1, kind = ?2, host_id = ?3, bind_host = ?4, bind_port = ?5,
+             target_host = ?6, target_port = ?7, auto_start = ?8, restart_on_failure = ?9
+         WHERE id = ?10",
+        rusqlite::params![
+            profile.name,
+            profile.kind.as_str(),
+            profile.host_id,
+            profile.bind_host.unwrap_or_else(|| "127.0.0.1".into()),
+            profile.bind_port,
+            profile.target_host,
+            profile.target_port,
+            profile.auto_start.as_str(),
+            profile.restart_on_failure,
+            id,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_tunnel_profile(id: i64, app: AppHandle, db: tauri::State<Db>) -> Result<(), String> {
+    stop_profile_tunnels(&app, id);
+
+    let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+    conn.execute("DELETE FROM tunnel_profiles WHERE id = ?", [id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn start_tunnel_profile(id: i64, app: AppHandle) -> Result<TunnelInfo, String> {
+    let profile = {
+        let db = app.state::<Db>();
+        let conn = db.conn.lock().map_err(|_| "DB lock failed")?;
+        load_profiles(&conn, "id = ?1", rusqlite::params![id])?
+            .pop()
+            .ok_or("Tunnel profile not found")?
+    };
+
+    if active_profile_ids().contains(&profile.id) {
+        return Err("Tunnel profile is already running".into());
+    }
+
+    start_tunnel(&app, profile.spec(), Some(profile.id), profile.restart_on_failure)
+}
+
+#[tauri::command]
+pub fn stop_tunnel_profile(id: i64, app: AppHandle) {
+    stop_profile_tunnels(&app, id);
+}
+
+/* =========================
+   AUTO START
+========================= */
+
+/// Starts every `app_launch` profile; called once from setup
+pub fn autostart_on_launch(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        autostart(
+            &app,
+            "auto_start = 'app_launch' AND host_id IN (SELECT id FROM hosts WHERE deleted_at IS NULL)",
+            rusqlite::params![],
+        );
+    });
+}
+
+/// Starts the host's `host_session` profiles that are not already running
+pub fn autostart_for_host(app: &AppHandle, host_id: i64) {
+    autostart(
+        app,
+        "auto_start = 'host_session' AND host_id = ?1",
+        rusqlite::params![host_id],
+    );
+}
+
+fn autostart(app: &AppHandle, filter: &str, params: &[&dyn rusqlite::ToSql]) {
+    let profiles = {
+        let db = app.state::<Db>();
+        let conn = match db.conn.lock() {
+            Ok(c) => c,
+            Err(_) => return,
+        };
+        match load_profiles(&conn, filter, params) {
+            Ok(p) => p,
+            Err(e) => {
+                println!("[Tunnel] Cannot load tunnel profiles: {}", e);
+                return;
+            }
+        }
+    };
+
+    let active = active_profile_ids();
+    for profile in profiles.iter().filter(|p| !active.contains(&p.id)) {
+        if let Err(e) = start_tunnel(app, profile.spec(), Some(profile.id), profile.restart_on_failure) {
+            println!("[Tunnel] Auto-start of '{}' failed: {}", profile.name, e);
+        }
+    }
+}
+
+/* =========================
+   HELPERS
+========================= */
+
+fn load_profiles(
+    conn: &Connection,
+    filter: &str,
+    params: &[&dyn rusqlite::ToSql],
+) -> Result<Vec<TunnelProfile>, String> {
+    let sql = format!(
+        "SELECT id, name, kind, host_id, bind_host, bind_port, target_host, target_port,
+                auto_start, restart_on_failure
+         FROM tunnel_profiles WHERE {} ORDER BY name",
+        filter
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map(params, |r| {
+            let kind: String = r.get(2)?;
+            let auto_start: String = r.get(8)?;
+            Ok(TunnelProfile {
+                id: r.get(0)?,
+                name: r.get(1)?,
+                kind: TunnelKind::parse(&kind).unwrap_or(TunnelKind::Local),
+                host_id: r.get(3)?,
+                bind_host: r.get(4)?,
+                bind_port: r.get(5)?,
+                target_host: r.get(6)?,
+                target_port: r.get(7)?,
+                auto_start: TunnelAutoStart::parse(&auto_start),
+                restart_on_failure: r.get(9)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.filter_map(Result::ok).collect())
+}
+
+fn validate(profile: &TunnelProfileInput) -> Result<(), String> {
+    if profile.name.trim().is_empty() {
+        return Err("Name is required".into());
+    }
+    if profile.bind_port == 0 && profile.kind != TunnelKind::Remote {
+        return Err("Bind port is required".into());
+    }
+    if profile.kind != TunnelKind::Dynamic
+        && (profile.target_host.trim().is_empty() || profile.target_port == 0)
+    {
+        return Err("Target host and port are required".into());
+    }
+    Ok(())
+}
@@ -0,0 +1,347 @@
+use rusqlite::Connection;
+use serde::Serialize;
+use std::{fmt, net::IpAddr};
+
+/* =========================
+   CONFIG
+========================= */
+
+pub const AUTH_TYPES: &[&str] = &["password", "key"];
+const MAX_NAME_LEN: usize = 255;
+const MAX_HOSTNAME_LEN: usize = 253;
+const MAX_LABEL_LEN: usize = 63;
+
+/* =========================
+   ERRORS
+========================= */
+
+/// Stable identifiers the frontend localizes; never rename a variant
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CrudErrorCode {
+    Required,
+    TooLong,
+    InvalidHost,
+    InvalidPort,
+    InvalidAuthType,
+    GroupNotFound,
+    HostNotFound,
+    InvalidParent,
+    GroupHasSubgroups,
+    GroupHasHosts,
+    Internal,
+}
+
+#[derive(Serialize, Debug)]
+pub struct CrudError {
+    pub code: CrudErrorCode,
+    /// Command argument the error is about, for highlighting the input
+    pub field: Option<&'static str>,
+    /// English fallback text
+    pub message: String,
+}
+
+impl CrudError {
+    pub fn new(code: CrudErrorCode, field: Option<&'static str>, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            field,
+            message: message.into(),
+        }
+    }
+
+    pub fn field(code: CrudErrorCode, field: &'static str, message: impl Into<String>) -> Self {
+        Self::new(code, Some(field), message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(CrudErrorCode::Internal, None, message)
+    }
+}
+
+impl fmt::Display for CrudError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<rusqlite::Error> for CrudError {
+    fn from(e: rusqlite::Error) -> Self {
+        Self::internal(e.to_string())
+    }
+}
+
+/// Lets helpers shared with `Result<_, String>` commands keep using `?`
+impl From<CrudError> for String {
+    fn from(e: CrudError) -> Self {
+        e.message
+    }
+}
+
+/* =========================
+   INPUT
+========================= */
+
+pub struct HostInput {
+    pub name: String,
+    pub host: String,
+    pub port: i64,
+    pub username: String,
+    pub auth_type: String,
+    pub group_id: Option<i64>,
+}
+
+impl HostInput {
+    /// Checks every field and returns the input with names trimmed
+    pub fn validate(self, conn: &Connection) -> Result<Self, CrudError> {
+        self.check(conn, true)
+    }
+
+    /// Same checks for hosts from an import, which may leave the user
+    /// empty for the SSH default
+    pub fn validate_imported(self, conn: &Connection) -> Result<Self, CrudError> {
+        self.check(conn, false)
+    }
+
+    fn check(self, conn: &Connection, username_required: bool) -> Result<Self, CrudError> {
+        let name = validate_name("name", &self.name)?;
+        let host = validate_host_address("host", &self.host)?;
+        let username = if username_required || !self.username.trim().is_empty() {
+            validate_name("username", &self.username)?
+        } else {
+            String::new()
+        };
+
+        if !(1..=65535).contains(&self.port) {
+            return Err(CrudError::field(
+                CrudErrorCode::InvalidPort,
+                "port",
+                "Port must be between 1 and 65535",
+            ));
+        }
+        if !AUTH_TYPES.contains(&self.auth_type.as_str()) {
+            return Err(CrudError::field(
+                CrudErrorCode::InvalidAuthType,
+                "auth_type",
+                format!("Unknown auth type '{}'", self.auth_type),
+            ));
+        }
+        if let Some(group_id) = self.group_id {
+            ensure_group(conn, group_id, "group_id")?;
+        }
+
+        Ok(Self {
+            name,
+            host,
+            username,
+            ..self
+        })
+    }
+}
+
+/* =========================
+   CHECKS
+========================= */
+
+/// Trimmed, non-empty and at most `MAX_NAME_LEN` characters
+pub fn validate_name(field: &'static str, value: &str) -> Result<String, CrudError> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(CrudError::field(
+            CrudErrorCode::Required,
+            field,
+            format!("{} is required", field),
+        ));
+    }
+    if value.chars().count() > MAX_NAME_LEN {
+        return Err(CrudError::field(
+            CrudErrorCode::TooLong,
+            field,
+            format!("{} is longer than {} characters", field, MAX_NAME_LEN),
+        ));
+    }
+    Ok(value.to_string())
+}
+
+/// An IPv4/IPv6 address or an RFC 1123 host name. Underscores are accepted
+/// because internal DNS zones use them.
+pub fn validate_host_address(field: &'static str, value: &str) -> Result<String, CrudError> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(CrudError::field(CrudErrorCode::Required, field, "Host is required"));
+    }
+
+    let unbracketed = value.strip_prefix('[').and_then(|v| v.strip_suffix(']'));
+    if unbracketed.unwrap_or(value).parse::<IpAddr>().is_ok() {
+        return Ok(unbracketed.unwrap_or(value).to_string());
+    }
+
+    let name = value.strip_suffix('.').unwrap_or(value);
+    let valid = name.len() <= MAX_HOSTNAME_LEN
+        && name.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= MAX_LABEL_LEN
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        })
+        // all-numeric names such as 10.0.0 are mistyped addresses
+        && !name.chars().all(|c| c.is_ascii_digit() || c == '.');
+
+    if !valid {
+        return Err(CrudError::field(
+            CrudErrorCode::InvalidHost,
+            field,
+            format!("'{}' is not a valid host name or IP address", value),
+        ));
+    }
+    Ok(value.to_string())
+}
+
+pub fn ensure_group(conn: &Connection, id: i64, field: &'static str) -> Result<(), CrudError> {
+    let exists: bool = conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM groups WHERE id = ? AND deleted_at IS NULL)",
+        [id],
+        |r| r.get(0),
+    )?;
+    if !exists {
+        return Err(CrudError::field(CrudErrorCode::GroupNotFound, field, "Group not found"));
+    }
+    Ok(())
+}
+
+pub fn ensure_host(conn: &Connection, id: i64) -> Result<(), CrudError> {
+    let exists: bool = conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM hosts WHERE id = ? AND deleted_at IS NULL)",
+        [id],
+        |r| r.get(0),
+    )?;
+    if !exists {
+        return Err(CrudError::field(CrudErrorCode::HostNotFound, "id", "Host not found"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn host_ok(value: &str) -> String {
+        validate_host_address("host", value).unwrap()
+    }
+
+    fn host_err(value: &str) -> CrudErrorCode {
+        validate_host_address("host", value).unwrap_err().code
+    }
+
+    fn input(port: i64) -> HostInput {
+        HostInput {
+            name: "web".into(),
+            host: "web.lan".into(),
+            port,
+            username: "deploy".into(),
+            auth_type: "password".into(),
+            group_id: None,
+        }
+    }
+
+    #[test]
+    fn ip_literals() {
+        assert_eq!(host_ok("10.0.0.1"), "10.0.0.1");
+        assert_eq!(host_ok("::1"), "::1");
+        assert_eq!(host_ok("fe80::1ff:fe23:4567:890a"), "fe80::1ff:fe23:4567:890a");
+        assert_eq!(host_ok("  2001:db8::1 "), "2001:db8::1");
+        assert_eq!(host_ok("::ffff:192.0.2.1"), "::ffff:192.0.2.1");
+        assert_eq!(host_err("2001:db8::1::2"), CrudErrorCode::InvalidHost);
+        assert_eq!(host_err("10.0.0"), CrudErrorCode::InvalidHost);
+        assert_eq!(host_err("256.1.1.1"), CrudErrorCode::InvalidHost);
+    }
+
+    #[test]
+    fn brackets_are_stripped_from_ip_literals_only() {
+        assert_eq!(host_ok("[::1]"), "::1");
+        assert_eq!(host_ok("[2001:db8::1]"), "2001:db8::1");
+        assert_eq!(host_ok("[10.0.0.1]"), "10.0.0.1");
+        assert_eq!(host_err("[web.lan]"), CrudErrorCode::InvalidHost);
+        assert_eq!(host_err("[::1"), CrudErrorCode::InvalidHost);
+        assert_eq!(host_err("::1]"), CrudErrorCode::InvalidHost);
+    }
+
+    #[test]
+    fn host_names() {
+        assert_eq!(host_ok("web-01.lan"), "web-01.lan");
+        assert_eq!(host_ok("db_primary.internal"), "db_primary.internal");
+        assert_eq!(host_ok("localhost"), "localhost");
+        assert_eq!(host_err(""), CrudErrorCode::Required);
+        assert_eq!(host_err("   "), CrudErrorCode::Required);
+        for bad in ["web lan", "-web.lan", "web-.lan", "web..lan", ".web.lan", "web/lan", "wéb.lan"] {
+            assert_eq!(host_err(bad), CrudErrorCode::InvalidHost, "{}", bad);
+        }
+    }
+
+    #[test]
+    fn trailing_dot_is_kept() {
+        assert_eq!(host_ok("web.lan."), "web.lan.");
+        assert_eq!(host_err("web.lan.."), CrudErrorCode::InvalidHost);
+        assert_eq!(host_err("."), CrudErrorCode::InvalidHost);
+        assert_eq!(host_err("10.0.0."), CrudErrorCode::InvalidHost);
+    }
+
+    #[test]
+    fn label_and_name_length() {
+        let label = "a".repeat(MAX_LABEL_LEN);
+        assert!(validate_host_address("host", &format!("{}.lan", label)).is_ok());
+        assert_eq!(host_err(&format!("{}a.lan", label)), CrudErrorCode::InvalidHost);
+
+        // 4 labels of 63 plus 3 dots is 255, one label less fits
+        let long = [label.as_str(); 4].join(".");
+        assert_eq!(host_err(&long), CrudErrorCode::InvalidHost);
+        let fits = format!("{}.{}", [label.as_str(); 3].join("."), "a".repeat(61));
+        assert_eq!(fits.len(), MAX_HOSTNAME_LEN);
+        assert!(validate_host_address("host", &fits).is_ok());
+        assert!(validate_host_address("host", &format!("{}.", fits)).is_ok());
+    }
+
+    #[test]
+    fn names_are_trimmed_and_bounded() {
+        assert_eq!(validate_name("name", "  web  ").unwrap(), "web");
+        assert_eq!(validate_name("name", " ").unwrap_err().code, CrudErrorCode::Required);
+        assert!(validate_name("name", &"é".repeat(MAX_NAME_LEN)).is_ok());
+        let err = validate_name("name", &"é".repeat(MAX_NAME_LEN + 1)).unwrap_err();
+        assert_eq!(err.code, CrudErrorCode::TooLong);
+        assert_eq!(err.field, Some("name"));
+    }
+
+    #[test]
+    fn port_bounds_and_fields() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        crate::migrations::run_migrations(&mut conn, Path::new("/tmp")).unwrap();
+
+        for port in [1, 22, 65535] {
+            assert!(input(port).validate(&conn).is_ok(), "{}", port);
+        }
+        for port in [0, -1, 65536] {
+            let err = input(port).validate(&conn).err().unwrap();
+            assert_eq!((err.code, err.field), (CrudErrorCode::InvalidPort, Some("port")), "{}", port);
+        }
+
+        let err = HostInput { auth_type: "agent".into(), ..input(22) }.validate(&conn).err().unwrap();
+        assert_eq!(err.code, CrudErrorCode::InvalidAuthType);
+        let err = HostInput { group_id: Some(42), ..input(22) }.validate(&conn).err().unwrap();
+        assert_eq!((err.code, err.field), (CrudErrorCode::GroupNotFound, Some("group_id")));
+    }
+
+    #[test]
+    fn imports_may_leave_the_username_empty() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        crate::migrations::run_migrations(&mut conn, Path::new("/tmp")).unwrap();
+
+        let err = HostInput { username: " ".into(), ..input(22) }.validate(&conn).err().unwrap();
+        assert_eq!((err.code, err.field), (CrudErrorCode::Required, Some("username")));
+
+        let imported = HostInput { username: " ".into(), ..input(22) }.validate_imported(&conn).unwrap();
+        assert_eq!(imported.username, "");
+        assert!(HostInput { port: 0, ..input(22) }.validate_imported(&conn).is_err());
+    }
+}
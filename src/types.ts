@@ -23,3 +23,10 @@ type SshExecResult = {
 export type SshResponse =
     | { ok: true; data: SshExecResult }
     | { ok: false; error: { kind: string; message: string } }
+
+/** Error returned by host/group CRUD commands; localize by `code` */
+export type CrudError = {
+    code: string
+    field: string | null
+    message: string
+}